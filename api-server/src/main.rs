@@ -0,0 +1,383 @@
+// A local JSON API over the item database, answering the same questions as
+// the website for things that aren't a browser.
+//
+//   api-server --db barohead/recipes.bincode --listen 127.0.0.1:8080
+
+use std::process::exit;
+use std::sync::Arc;
+
+use barohead_data::{database, items as data, items::StoreIdentifier};
+use barohead_db::{ItemRef, ProcessRef, StoreSummary, DB, INTERESTING_MERCHANTS};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Response, Server};
+
+struct Args {
+    db: String,
+    listen: String,
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: api-server [--db FILE] [--listen ADDR]");
+    exit(1);
+}
+
+fn parse_args() -> Args {
+    let mut parsed = Args {
+        db: "barohead/recipes.bincode".to_owned(),
+        listen: "127.0.0.1:8080".to_owned(),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--db" => parsed.db = value,
+            "--listen" => parsed.listen = value,
+            _ => usage(),
+        }
+    }
+    parsed
+}
+
+#[derive(Serialize)]
+struct ItemSummary<'a> {
+    id: &'a str,
+    name: &'a str,
+}
+
+impl<'a> ItemSummary<'a> {
+    fn new(db: &'a DB, item_ref: ItemRef) -> Result<Self, ApiError> {
+        Ok(Self {
+            id: &db.get_item(item_ref)?.id,
+            name: db.item_translations.get_name(item_ref)?,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct ItemResponse<'a> {
+    #[serde(flatten)]
+    summary: ItemSummary<'a>,
+    item: &'a data::Item,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum ProcessResponse<'a> {
+    Fabricate {
+        item: ItemSummary<'a>,
+        index: usize,
+        recipe: &'a data::Fabricate,
+    },
+    Deconstruct {
+        item: ItemSummary<'a>,
+        index: usize,
+        recipe: &'a data::Deconstruct,
+    },
+}
+
+impl<'a> ProcessResponse<'a> {
+    fn new(db: &'a DB, process_ref: &ProcessRef) -> Result<Self, ApiError> {
+        Ok(match process_ref {
+            ProcessRef::Fabricate(fabricate_ref) => ProcessResponse::Fabricate {
+                item: ItemSummary::new(db, fabricate_ref.item_ref)?,
+                index: fabricate_ref.idx,
+                recipe: db.get_fabricate(fabricate_ref)?,
+            },
+            ProcessRef::Deconstruct(deconstruct_ref) => ProcessResponse::Deconstruct {
+                item: ItemSummary::new(db, deconstruct_ref.item_ref)?,
+                index: deconstruct_ref.idx,
+                recipe: db.get_deconstruct(deconstruct_ref)?,
+            },
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct SearchResponse<'a> {
+    #[serde(flatten)]
+    item: ItemSummary<'a>,
+    score: i64,
+}
+
+#[derive(Serialize)]
+struct StoreItem<'a> {
+    #[serde(flatten)]
+    item: ItemSummary<'a>,
+    /// What the store charges.
+    purchase_price: i32,
+    /// What the store pays.
+    sale_price: i32,
+}
+
+#[derive(Serialize)]
+struct StoreResponse<'a> {
+    id: &'a str,
+    name: &'a str,
+    items: Vec<StoreItem<'a>>,
+}
+
+enum ApiError {
+    NotFound(String),
+    BadRequest(String),
+    /// The database doesn't agree with itself. Not the client's fault.
+    Internal(String),
+}
+
+impl From<barohead_db::Error> for ApiError {
+    fn from(err: barohead_db::Error) -> Self {
+        ApiError::Internal(err.to_string())
+    }
+}
+
+fn lookup_item(db: &DB, id: &str) -> Result<ItemRef, ApiError> {
+    db.new_item_ref(id)
+        .map_err(|err| ApiError::NotFound(err.to_string()))
+}
+
+fn processes(db: &DB, refs: Option<Arc<Vec<ProcessRef>>>) -> Result<Value, ApiError> {
+    let refs = refs.unwrap_or_default();
+    let processes = refs
+        .iter()
+        .map(|process_ref| ProcessResponse::new(db, process_ref))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(json!(processes))
+}
+
+fn store(db: &DB, store: StoreIdentifier) -> Result<Value, ApiError> {
+    let mut items = Vec::new();
+    for item_ref in db.item_refs() {
+        let Some(price) = &db.get_item(item_ref)?.price else {
+            continue;
+        };
+        let pricing = StoreSummary::for_store(price, store);
+        if let Some(sell) = pricing.sell {
+            items.push(StoreItem {
+                item: ItemSummary::new(db, item_ref)?,
+                purchase_price: sell,
+                sale_price: pricing.buy,
+            });
+        }
+    }
+    Ok(json!(StoreResponse {
+        id: store.internal_name(),
+        name: db.store_translations.get_name(&store)?,
+        items,
+    }))
+}
+
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (key == name).then(|| url_escape::decode(&value.replace('+', " ")).into_owned())
+    })
+}
+
+fn route(db: &DB, url: &str) -> Result<Value, ApiError> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| url_escape::decode(segment).into_owned())
+        .collect::<Vec<_>>();
+    let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match segments.as_slice() {
+        ["items", id] => {
+            let item_ref = lookup_item(db, id)?;
+            Ok(json!(ItemResponse {
+                summary: ItemSummary::new(db, item_ref)?,
+                item: db.get_item(item_ref)?,
+            }))
+        }
+        ["items", id, "used_by"] => {
+            let item_ref = lookup_item(db, id)?;
+            processes(db, db.get_used_by(item_ref))
+        }
+        ["items", id, "produced_by"] => {
+            let item_ref = lookup_item(db, id)?;
+            processes(db, db.get_produced_by(item_ref))
+        }
+        ["search"] => {
+            let q = query_param(query, "q")
+                .ok_or_else(|| ApiError::BadRequest("Missing query parameter q".to_owned()))?;
+            let results = db
+                .search(&q)
+                .into_iter()
+                .map(|result| {
+                    Ok(SearchResponse {
+                        item: ItemSummary::new(db, result.item_ref)?,
+                        score: result.score,
+                    })
+                })
+                .collect::<Result<Vec<_>, ApiError>>()?;
+            Ok(json!(results))
+        }
+        ["stores", id] => StoreIdentifier::from_internal_name(id)
+            .filter(|store| INTERESTING_MERCHANTS.contains(store))
+            .ok_or_else(|| ApiError::NotFound(format!("No such store: {}", id)))
+            .and_then(|s| store(db, s)),
+        _ => Err(ApiError::NotFound(format!("No such endpoint: {}", path))),
+    }
+}
+
+fn serve(db: &DB, server: &Server) {
+    let headers = [
+        Header::from_bytes("Content-Type", "application/json").unwrap(),
+        Header::from_bytes("Access-Control-Allow-Origin", "*").unwrap(),
+    ];
+
+    for request in server.incoming_requests() {
+        let (status, body) = match route(db, request.url()) {
+            Ok(body) => (200, body),
+            Err(ApiError::NotFound(err)) => (404, json!({ "error": err })),
+            Err(ApiError::BadRequest(err)) => (400, json!({ "error": err })),
+            Err(ApiError::Internal(err)) => {
+                eprintln!("{}: {}", request.url(), err);
+                (500, json!({ "error": err }))
+            }
+        };
+        let mut response = Response::from_string(body.to_string()).with_status_code(status);
+        for header in &headers {
+            response.add_header(header.clone());
+        }
+        if let Err(err) = request.respond(response) {
+            eprintln!("Failed to respond: {}", err);
+        }
+    }
+}
+
+fn main() {
+    let args = parse_args();
+
+    let bytes = std::fs::read(&args.db).unwrap_or_else(|err| {
+        eprintln!("{}: {}", args.db, err);
+        exit(1);
+    });
+    let (_header, itemdb) = database::read(&bytes).unwrap_or_else(|err| {
+        eprintln!("{}: {}", args.db, err);
+        exit(1);
+    });
+    let db = DB::new(itemdb).unwrap_or_else(|err| {
+        eprintln!("{}: {}", args.db, err);
+        exit(1);
+    });
+
+    let server = Server::http(&args.listen).unwrap_or_else(|err| {
+        eprintln!("Unable to listen on {}: {}", args.listen, err);
+        exit(1);
+    });
+    eprintln!("Listening on http://{}", args.listen);
+
+    serve(&db, &server);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+
+    use barohead_data::items::Price;
+    use barohead_db::test_support::{deconstruct, fabricate, item, itemdb};
+
+    use super::*;
+
+    fn fixture() -> DB {
+        let mut copper = item("copper");
+        copper.price = Some(Price {
+            baseprice: 40,
+            ..Default::default()
+        });
+        let mut wire = item("wire");
+        wire.fabricate.push(data::Fabricate {
+            time: 10.0,
+            ..fabricate(&[("copper", 2)], 1)
+        });
+        wire.deconstruct.push(data::Deconstruct {
+            time: 5.0,
+            ..deconstruct(&[("copper", 1)])
+        });
+        DB::new(itemdb(
+            vec![copper, wire],
+            &[
+                ("entityname.copper", "Copper"),
+                ("entityname.wire", "Wire"),
+                ("storename.merchantcity", "City"),
+            ],
+        ))
+        .unwrap()
+    }
+
+    fn start() -> SocketAddr {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        std::thread::spawn(move || serve(&fixture(), &server));
+        addr
+    }
+
+    fn get(addr: SocketAddr, path: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {} HTTP/1.0\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn items() {
+        let addr = start();
+        let (status, body) = get(addr, "/items/wire");
+        assert_eq!(status, 200);
+        assert_eq!(body["id"], "wire");
+        assert_eq!(body["name"], "Wire");
+        assert_eq!(body["item"]["fabricate"][0]["amount"], 1);
+
+        let (status, body) = get(addr, "/items/tin");
+        assert_eq!(status, 404);
+        assert!(body["error"].is_string());
+    }
+
+    #[test]
+    fn used_by_and_produced_by() {
+        let addr = start();
+        let (status, body) = get(addr, "/items/copper/used_by");
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["kind"], "fabricate");
+        assert_eq!(body[0]["item"]["id"], "wire");
+        assert_eq!(body[0]["index"], 0);
+
+        let (status, body) = get(addr, "/items/copper/produced_by");
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["kind"], "deconstruct");
+        assert_eq!(body[0]["item"]["id"], "wire");
+
+        assert_eq!(get(addr, "/items/wire/used_by"), (200, json!([])));
+        assert_eq!(get(addr, "/items/tin/used_by").0, 404);
+    }
+
+    #[test]
+    fn search() {
+        let addr = start();
+        let (status, body) = get(addr, "/search?q=copp");
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["id"], "copper");
+
+        assert_eq!(get(addr, "/search").0, 400);
+    }
+
+    #[test]
+    fn stores() {
+        let addr = start();
+        let (status, body) = get(addr, "/stores/merchantcity");
+        assert_eq!(status, 200);
+        assert_eq!(body["name"], "City");
+        assert_eq!(body["items"][0]["id"], "copper");
+        assert!(body["items"][0]["purchase_price"].is_number());
+
+        assert_eq!(get(addr, "/stores/nowhere").0, 404);
+        assert_eq!(get(addr, "/nothing").0, 404);
+    }
+}
@@ -0,0 +1,802 @@
+// The website's answers, for people who'd rather stay in a terminal.
+//
+//   barohead [--db FILE] [--json] show ITEM
+//   barohead [--db FILE] [--json] used-by ITEM
+//   barohead [--db FILE] [--json] produced-by ITEM
+//   barohead [--db FILE] [--json] price ITEM [--store STORE]
+//   barohead [--db FILE] [--json] search QUERY
+//   barohead [--db FILE] [--json] bom ITEM [--qty N]
+//   barohead [--db FILE] graph [ITEM] [--format dot|mermaid|graphml]
+//            [--depth N | --up N --down N] [--out FILE]
+//   barohead [--db FILE] tui
+//
+// ITEM is an item identifier or its English name.
+
+mod table;
+mod tui;
+
+use std::fmt;
+use std::io::{self, Write};
+use std::process::exit;
+use std::sync::Arc;
+
+use barohead_data::{database, items as data, items::StoreIdentifier};
+use barohead_db::{
+    GraphFormat, ItemRef, Material, ProcessRef, RecipeGraph, StoreSummary, DB,
+    INTERESTING_MERCHANTS,
+};
+use serde::Serialize;
+
+use table::Table;
+
+enum Command {
+    Show(String),
+    UsedBy(String),
+    ProducedBy(String),
+    Price { item: String, store: Option<String> },
+    Search(String),
+    Bom { item: String, quantity: i32 },
+    Graph(GraphArgs),
+    Tui,
+}
+
+struct GraphArgs {
+    /// Without an item, the whole database.
+    item: Option<String>,
+    format: GraphFormat,
+    upstream: usize,
+    downstream: usize,
+    out: Option<String>,
+}
+
+struct Args {
+    db: String,
+    json: bool,
+    command: Command,
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: barohead [--db FILE] [--json] COMMAND");
+    eprintln!();
+    eprintln!("Commands:");
+    eprintln!("  show ITEM");
+    eprintln!("  used-by ITEM");
+    eprintln!("  produced-by ITEM");
+    eprintln!("  price ITEM [--store STORE]");
+    eprintln!("  search QUERY");
+    eprintln!("  bom ITEM [--qty N]");
+    eprintln!(
+        "  graph [ITEM] [--format dot|mermaid|graphml] [--depth N | --up N --down N] [--out FILE]"
+    );
+    eprintln!("  tui");
+    exit(1);
+}
+
+fn parse_args() -> Args {
+    let mut db = "barohead/recipes.bincode".to_owned();
+    let mut json = false;
+    let mut store = None;
+    let mut quantity = 1;
+    let mut graph = GraphArgs {
+        item: None,
+        format: GraphFormat::Dot,
+        upstream: 1,
+        downstream: 1,
+        out: None,
+    };
+    let mut positional = vec![];
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--db" => db = args.next().unwrap_or_else(|| usage()),
+            "--json" => json = true,
+            "--store" => store = Some(args.next().unwrap_or_else(|| usage())),
+            "--qty" => {
+                quantity = args
+                    .next()
+                    .and_then(|qty| qty.parse().ok())
+                    .filter(|qty| *qty > 0)
+                    .unwrap_or_else(|| usage())
+            }
+            "--format" => {
+                graph.format = args
+                    .next()
+                    .and_then(|format| GraphFormat::from_name(&format))
+                    .unwrap_or_else(|| usage())
+            }
+            "--depth" => {
+                graph.upstream = depth(args.next());
+                graph.downstream = graph.upstream;
+            }
+            "--up" => graph.upstream = depth(args.next()),
+            "--down" => graph.downstream = depth(args.next()),
+            "--out" => graph.out = Some(args.next().unwrap_or_else(|| usage())),
+            _ if arg.starts_with("--") => usage(),
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let command = positional.next().unwrap_or_else(|| usage());
+    let argument = positional.next();
+    if positional.next().is_some() {
+        usage();
+    }
+    let command = match (command.as_str(), argument) {
+        ("show", Some(item)) => Command::Show(item),
+        ("used-by", Some(item)) => Command::UsedBy(item),
+        ("produced-by", Some(item)) => Command::ProducedBy(item),
+        ("price", Some(item)) => Command::Price { item, store },
+        ("search", Some(query)) => Command::Search(query),
+        ("bom", Some(item)) => Command::Bom { item, quantity },
+        ("graph", item) => Command::Graph(GraphArgs { item, ..graph }),
+        ("tui", None) => Command::Tui,
+        _ => usage(),
+    };
+
+    Args { db, json, command }
+}
+
+fn depth(arg: Option<String>) -> usize {
+    arg.and_then(|depth| depth.parse().ok())
+        .unwrap_or_else(|| usage())
+}
+
+fn find_item(db: &DB, query: &str) -> Result<ItemRef, String> {
+    if let Ok(item_ref) = db.new_item_ref(query) {
+        return Ok(item_ref);
+    }
+
+    // Searching is case sensitive once the query has capitals, but names
+    // typed on the command line shouldn't have to be.
+    let results = db.search(&query.to_lowercase());
+    let exact = results.iter().find(|result| {
+        db.item_translations
+            .get_name(result.item_ref)
+            .is_ok_and(|name| name.eq_ignore_ascii_case(query))
+    });
+    if let Some(result) = exact {
+        return Ok(result.item_ref);
+    }
+
+    let suggestions = results
+        .iter()
+        .take(5)
+        .map(|result| ItemSummary::new(db, result.item_ref).to_string())
+        .collect::<Vec<_>>();
+    if suggestions.is_empty() {
+        Err(format!("No item matches {:?}", query))
+    } else {
+        Err(format!(
+            "No item matches {:?}, did you mean one of: {}",
+            query,
+            suggestions.join(", ")
+        ))
+    }
+}
+
+fn find_store(db: &DB, query: &str) -> Result<StoreIdentifier, String> {
+    StoreIdentifier::from_internal_name(query)
+        .or_else(|| {
+            StoreIdentifier::ALL.into_iter().find(|store| {
+                db.store_translations
+                    .get_name(store)
+                    .is_ok_and(|name| name.eq_ignore_ascii_case(query))
+            })
+        })
+        .ok_or_else(|| format!("No store matches {:?}", query))
+}
+
+#[derive(Serialize)]
+struct ItemSummary<'a> {
+    id: &'a str,
+    name: &'a str,
+}
+
+impl<'a> ItemSummary<'a> {
+    fn new(db: &'a DB, item_ref: ItemRef) -> Self {
+        Self {
+            id: &db.get_item(item_ref).unwrap().id,
+            name: db.item_translations.get_name(item_ref).unwrap(),
+        }
+    }
+}
+
+impl fmt::Display for ItemSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.id)
+    }
+}
+
+/// An item or tag with an amount, as recipes list them.
+#[derive(Serialize)]
+struct Amount<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tag: Option<String>,
+    name: String,
+    amount: i32,
+}
+
+impl<'a> Amount<'a> {
+    fn item(db: &'a DB, id: &'a str, amount: i32) -> Self {
+        let name = db
+            .new_item_ref(id)
+            .and_then(|item_ref| db.item_translations.get_name(item_ref))
+            .unwrap_or(id);
+        Self {
+            id: Some(id),
+            tag: None,
+            name: name.to_owned(),
+            amount,
+        }
+    }
+
+    fn required(db: &'a DB, required_item: &'a data::RequiredItem) -> Self {
+        match &required_item.item {
+            data::ItemRef::Id(id) => Self::item(db, id, required_item.amount),
+            data::ItemRef::Tag(tag) => Self {
+                id: None,
+                tag: Some(tag.clone()),
+                name: format!("any {}", tag),
+                amount: required_item.amount,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Amount<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.amount == 1 {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{}x {}", self.amount, self.name)
+        }
+    }
+}
+
+fn join(amounts: &[Amount]) -> String {
+    amounts
+        .iter()
+        .map(Amount::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ProcessKind {
+    Fabricate,
+    Deconstruct,
+}
+
+#[derive(Serialize)]
+struct Process<'a> {
+    kind: ProcessKind,
+    item: ItemSummary<'a>,
+    index: usize,
+    time: f32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fabricators: Vec<&'a data::Fabricator>,
+    inputs: Vec<Amount<'a>>,
+    outputs: Vec<Amount<'a>>,
+}
+
+impl<'a> Process<'a> {
+    fn fabricate(db: &'a DB, item_ref: ItemRef, index: usize) -> Self {
+        let item = db.get_item(item_ref).unwrap();
+        let fabricate = &item.fabricate[index];
+        Self {
+            kind: ProcessKind::Fabricate,
+            item: ItemSummary::new(db, item_ref),
+            index,
+            time: fabricate.time,
+            fabricators: fabricate.suitable_fabricators.iter().collect(),
+            inputs: fabricate
+                .required_items
+                .iter()
+                .map(|required_item| Amount::required(db, required_item))
+                .collect(),
+            outputs: vec![Amount::item(db, &item.id, fabricate.amount)],
+        }
+    }
+
+    fn deconstruct(db: &'a DB, item_ref: ItemRef, index: usize) -> Self {
+        let item = db.get_item(item_ref).unwrap();
+        let deconstruct = &item.deconstruct[index];
+        let mut inputs = vec![Amount::item(db, &item.id, 1)];
+        inputs.extend(
+            deconstruct
+                .required_items
+                .iter()
+                .map(|required_item| Amount::required(db, required_item)),
+        );
+        Self {
+            kind: ProcessKind::Deconstruct,
+            item: ItemSummary::new(db, item_ref),
+            index,
+            time: deconstruct.time,
+            fabricators: vec![],
+            inputs,
+            outputs: deconstruct
+                .items
+                .iter()
+                .map(|produced_item| Amount::item(db, &produced_item.id, produced_item.amount))
+                .collect(),
+        }
+    }
+
+    fn new(db: &'a DB, process_ref: &ProcessRef) -> Self {
+        match process_ref {
+            ProcessRef::Fabricate(fabricate_ref) => {
+                Self::fabricate(db, fabricate_ref.item_ref, fabricate_ref.idx)
+            }
+            ProcessRef::Deconstruct(deconstruct_ref) => {
+                Self::deconstruct(db, deconstruct_ref.item_ref, deconstruct_ref.idx)
+            }
+        }
+    }
+
+    fn location(&self) -> String {
+        match self.kind {
+            ProcessKind::Fabricate => self
+                .fabricators
+                .iter()
+                .map(|fabricator| fabricator.name())
+                .collect::<Vec<_>>()
+                .join(", "),
+            ProcessKind::Deconstruct => "Deconstructor".to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Processes<'a>(Vec<Process<'a>>);
+
+impl fmt::Display for Processes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = Table::new(&["Item", "Where", "Time", "Inputs", "Outputs"]);
+        for process in &self.0 {
+            table.row(vec![
+                process.item.to_string(),
+                process.location(),
+                format!("{}s", process.time),
+                join(&process.inputs),
+                join(&process.outputs),
+            ]);
+        }
+        if table.is_empty() {
+            writeln!(f, "None")
+        } else {
+            write!(f, "{}", table)
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct StorePrice<'a> {
+    id: &'static str,
+    name: &'a str,
+    /// What the store charges, if it sells the item at all.
+    purchase_price: Option<i32>,
+    /// What the store pays.
+    sale_price: i32,
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Prices<'a>(Vec<StorePrice<'a>>);
+
+impl<'a> Prices<'a> {
+    fn new(db: &'a DB, price: &data::Price, stores: &[StoreIdentifier]) -> Self {
+        Self(
+            stores
+                .iter()
+                .map(|store| {
+                    let summary = StoreSummary::for_store(price, *store);
+                    StorePrice {
+                        id: store.internal_name(),
+                        name: db.store_translations.get_name(store).unwrap(),
+                        purchase_price: summary.sell,
+                        sale_price: summary.buy,
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for Prices<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = Table::new(&["Store", "Buy for", "Sell for"]);
+        for store in &self.0 {
+            table.row(vec![
+                store.name.to_owned(),
+                store
+                    .purchase_price
+                    .map_or_else(|| "-".to_owned(), |price| format!("{} mk", price)),
+                format!("{} mk", store.sale_price),
+            ]);
+        }
+        write!(f, "{}", table)
+    }
+}
+
+#[derive(Serialize)]
+struct ItemReport<'a> {
+    #[serde(flatten)]
+    item: ItemSummary<'a>,
+    fabricate: Processes<'a>,
+    deconstruct: Processes<'a>,
+    prices: Option<Prices<'a>>,
+    used_by: usize,
+    produced_by: usize,
+}
+
+impl fmt::Display for ItemReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.item)?;
+        writeln!(f)?;
+        writeln!(f, "Fabricate:")?;
+        writeln!(f, "{}", self.fabricate)?;
+        writeln!(f, "Deconstruct:")?;
+        writeln!(f, "{}", self.deconstruct)?;
+        if let Some(prices) = &self.prices {
+            writeln!(f, "Prices:")?;
+            writeln!(f, "{}", prices)?;
+        }
+        writeln!(
+            f,
+            "Used by {} recipes, produced by {}.",
+            self.used_by, self.produced_by
+        )
+    }
+}
+
+#[derive(Serialize)]
+struct SearchHit<'a> {
+    #[serde(flatten)]
+    item: ItemSummary<'a>,
+    score: i64,
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+struct SearchReport<'a>(Vec<SearchHit<'a>>);
+
+impl fmt::Display for SearchReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = Table::new(&["Name", "Id", "Score"]);
+        for hit in &self.0 {
+            table.row(vec![
+                hit.item.name.to_owned(),
+                hit.item.id.to_owned(),
+                hit.score.to_string(),
+            ]);
+        }
+        write!(f, "{}", table)
+    }
+}
+
+#[derive(Serialize)]
+struct BomStep<'a> {
+    #[serde(flatten)]
+    item: ItemSummary<'a>,
+    runs: i32,
+    needed: i32,
+    produced: i32,
+    fabricators: Vec<&'a data::Fabricator>,
+    time: f32,
+}
+
+#[derive(Serialize)]
+struct BomReport<'a> {
+    item: ItemSummary<'a>,
+    quantity: i32,
+    steps: Vec<BomStep<'a>>,
+    materials: Vec<Amount<'a>>,
+}
+
+impl fmt::Display for BomReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}x {}", self.quantity, self.item)?;
+        writeln!(f)?;
+
+        writeln!(f, "Fabricate, in order:")?;
+        let mut steps = Table::new(&["Item", "Runs", "Makes", "Where", "Time"]);
+        for step in &self.steps {
+            let spare = step.produced - step.needed;
+            steps.row(vec![
+                step.item.to_string(),
+                step.runs.to_string(),
+                if spare > 0 {
+                    format!("{} ({} spare)", step.produced, spare)
+                } else {
+                    step.produced.to_string()
+                },
+                step.fabricators
+                    .iter()
+                    .map(|fabricator| fabricator.name())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                format!("{}s", step.time),
+            ]);
+        }
+        writeln!(f, "{}", steps)?;
+
+        writeln!(f, "Materials:")?;
+        let mut materials = Table::new(&["Material", "Amount"]);
+        for material in &self.materials {
+            materials.row(vec![material.name.clone(), material.amount.to_string()]);
+        }
+        write!(f, "{}", materials)
+    }
+}
+
+fn processes(db: &DB, item_ref: ItemRef) -> (Processes<'_>, Processes<'_>) {
+    let item = db.get_item(item_ref).unwrap();
+    let fabricate = (0..item.fabricate.len())
+        .map(|idx| Process::fabricate(db, item_ref, idx))
+        .collect();
+    let deconstruct = (0..item.deconstruct.len())
+        .map(|idx| Process::deconstruct(db, item_ref, idx))
+        .collect();
+    (Processes(fabricate), Processes(deconstruct))
+}
+
+fn referencing_processes(db: &DB, refs: Option<Arc<Vec<ProcessRef>>>) -> Processes<'_> {
+    Processes(
+        refs.unwrap_or_default()
+            .iter()
+            .map(|process_ref| Process::new(db, process_ref))
+            .collect(),
+    )
+}
+
+fn show(db: &DB, item_ref: ItemRef) -> ItemReport<'_> {
+    let (fabricate, deconstruct) = processes(db, item_ref);
+    ItemReport {
+        item: ItemSummary::new(db, item_ref),
+        fabricate,
+        deconstruct,
+        prices: db
+            .get_item(item_ref)
+            .unwrap()
+            .price
+            .as_ref()
+            .map(|price| Prices::new(db, price, &INTERESTING_MERCHANTS)),
+        used_by: db.get_used_by(item_ref).map_or(0, |refs| refs.len()),
+        produced_by: db.get_produced_by(item_ref).map_or(0, |refs| refs.len()),
+    }
+}
+
+fn bom(db: &DB, item_ref: ItemRef, quantity: i32) -> Result<BomReport<'_>, String> {
+    let bom = db
+        .bill_of_materials(&[(item_ref, quantity)])
+        .map_err(|err| err.to_string())?;
+
+    let steps = bom
+        .steps
+        .iter()
+        .map(|step| {
+            let fabricate = db.get_fabricate(&step.fabricate_ref).unwrap();
+            BomStep {
+                item: ItemSummary::new(db, step.fabricate_ref.item_ref),
+                runs: step.runs,
+                needed: step.needed,
+                produced: step.runs * fabricate.amount,
+                fabricators: fabricate.suitable_fabricators.iter().collect(),
+                time: step.runs as f32 * fabricate.time,
+            }
+        })
+        .collect();
+
+    let materials = bom
+        .materials
+        .iter()
+        .map(|(material, amount)| match material {
+            Material::Item(item_ref) => {
+                Amount::item(db, &db.get_item(*item_ref).unwrap().id, *amount)
+            }
+            Material::Tag(tag) => Amount {
+                id: None,
+                tag: Some(tag.clone()),
+                name: format!("any {}", tag),
+                amount: *amount,
+            },
+        })
+        .collect();
+
+    Ok(BomReport {
+        item: ItemSummary::new(db, item_ref),
+        quantity,
+        steps,
+        materials,
+    })
+}
+
+fn graph(db: &DB, args: GraphArgs) -> Result<(), String> {
+    let graph = match args.item {
+        Some(item) => {
+            RecipeGraph::around(db, find_item(db, &item)?, args.upstream, args.downstream)
+        }
+        None => RecipeGraph::all(db),
+    }
+    .and_then(|graph| graph.render(db, args.format))
+    .map_err(|err| err.to_string())?;
+
+    match args.out {
+        Some(path) => std::fs::write(&path, graph).map_err(|err| format!("{}: {}", path, err)),
+        None => {
+            let _ = io::stdout().write_all(graph.as_bytes());
+            Ok(())
+        }
+    }
+}
+
+fn print(report: impl Serialize + fmt::Display, json: bool) {
+    let output = if json {
+        serde_json::to_string_pretty(&report).unwrap() + "\n"
+    } else {
+        report.to_string()
+    };
+    // Piping into `head` and the like closes stdout early, which is fine.
+    let _ = io::stdout().write_all(output.as_bytes());
+}
+
+fn run(db: &DB, command: Command, json: bool) -> Result<(), String> {
+    match command {
+        Command::Show(item) => print(show(db, find_item(db, &item)?), json),
+        Command::UsedBy(item) => {
+            let item_ref = find_item(db, &item)?;
+            print(referencing_processes(db, db.get_used_by(item_ref)), json)
+        }
+        Command::ProducedBy(item) => {
+            let item_ref = find_item(db, &item)?;
+            print(
+                referencing_processes(db, db.get_produced_by(item_ref)),
+                json,
+            )
+        }
+        Command::Price { item, store } => {
+            let item_ref = find_item(db, &item)?;
+            let stores = match store {
+                Some(store) => vec![find_store(db, &store)?],
+                None => INTERESTING_MERCHANTS.to_vec(),
+            };
+            let price = db
+                .get_item(item_ref)
+                .unwrap()
+                .price
+                .as_ref()
+                .ok_or_else(|| format!("{} isn't sold anywhere", ItemSummary::new(db, item_ref)))?;
+            print(Prices::new(db, price, &stores), json)
+        }
+        Command::Search(query) => {
+            let hits = db
+                .search(&query)
+                .into_iter()
+                .map(|result| SearchHit {
+                    item: ItemSummary::new(db, result.item_ref),
+                    score: result.score,
+                })
+                .collect();
+            print(SearchReport(hits), json)
+        }
+        Command::Bom { item, quantity } => print(bom(db, find_item(db, &item)?, quantity)?, json),
+        Command::Graph(args) => graph(db, args)?,
+        Command::Tui => tui::run(db).map_err(|err| err.to_string())?,
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = parse_args();
+
+    let bytes = std::fs::read(&args.db).unwrap_or_else(|err| {
+        eprintln!("{}: {}", args.db, err);
+        exit(1);
+    });
+    let (_header, itemdb) = database::read(&bytes).unwrap_or_else(|err| {
+        eprintln!("{}: {}", args.db, err);
+        exit(1);
+    });
+    let db = DB::new(itemdb).unwrap_or_else(|err| {
+        eprintln!("{}: {}", args.db, err);
+        exit(1);
+    });
+
+    if let Err(err) = run(&db, args.command, args.json) {
+        eprintln!("{}", err);
+        exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use barohead_db::test_support::{fabricate, item, itemdb};
+
+    use super::*;
+
+    fn fixture() -> DB {
+        let mut wire = item("wire");
+        wire.fabricate.push(fabricate(&[("copper", 1)], 2));
+        let mut fuse = item("fuse");
+        fuse.fabricate
+            .push(fabricate(&[("wire", 3), ("#smallitem", 1)], 1));
+        DB::new(itemdb(
+            vec![item("copper"), wire, fuse],
+            &[
+                ("entityname.copper", "Copper"),
+                ("entityname.wire", "Copper Wire"),
+                ("entityname.fuse", "Fuse"),
+            ],
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_items_by_id_or_name() {
+        let db = fixture();
+        let wire = db.new_item_ref("wire").unwrap();
+        assert_eq!(find_item(&db, "wire"), Ok(wire));
+        assert_eq!(find_item(&db, "copper WIRE"), Ok(wire));
+        assert_eq!(
+            find_item(&db, "cop"),
+            Err(
+                "No item matches \"cop\", did you mean one of: Copper (copper), Copper Wire (wire)"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            find_item(&db, "railgun"),
+            Err("No item matches \"railgun\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn lists_processes() {
+        let db = fixture();
+        let copper = db.new_item_ref("copper").unwrap();
+        assert_eq!(
+            referencing_processes(&db, db.get_used_by(copper)).to_string(),
+            "\
+Item                Where       Time  Inputs  Outputs
+------------------  ----------  ----  ------  --------------
+Copper Wire (wire)  Fabricator  1s    Copper  2x Copper Wire
+"
+        );
+        let fuse = db.new_item_ref("fuse").unwrap();
+        assert_eq!(
+            referencing_processes(&db, db.get_used_by(fuse)).to_string(),
+            "None\n"
+        );
+    }
+
+    #[test]
+    fn reports_a_bill_of_materials() {
+        let db = fixture();
+        let fuse = db.new_item_ref("fuse").unwrap();
+        assert_eq!(
+            bom(&db, fuse, 1).unwrap().to_string(),
+            "\
+1x Fuse (fuse)
+
+Fabricate, in order:
+Item                Runs  Makes        Where       Time
+------------------  ----  -----------  ----------  ----
+Copper Wire (wire)  2     4 (1 spare)  Fabricator  2s
+Fuse (fuse)         1     1            Fabricator  1s
+
+Materials:
+Material       Amount
+-------------  ------
+Copper         2
+any smallitem  1
+"
+        );
+    }
+}
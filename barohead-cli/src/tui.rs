@@ -0,0 +1,552 @@
+// A full-screen browser over the database, laid out like the website: search
+// on the left, the item on the right, and links between items that can be
+// followed from the keyboard.
+//
+//   Typing        search (while the search box has focus)
+//   Up/Down       move through search results or links
+//   Enter         open the selected item or follow the selected link
+//   Tab           switch between search and the item
+//   /             go back to searching (while the item has focus)
+//   Backspace     go back (while the item has focus)
+//   q, Esc        quit (while the item has focus)
+//   Esc           quit (while searching with no item open)
+//   Ctrl-C        quit
+
+use std::io::{self, Stdout};
+
+use barohead_db::{ItemRef, DB, INTERESTING_MERCHANTS};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::ExecutableCommand;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Row, Table};
+use ratatui::{Frame, Terminal};
+
+use crate::{join, processes, referencing_processes, Amount, ItemSummary, Prices, Process};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Focus {
+    Search,
+    Item,
+}
+
+/// A line in the item pane, which might lead to another item.
+struct DetailLine {
+    line: Line<'static>,
+    link: Option<ItemRef>,
+}
+
+impl DetailLine {
+    fn heading(text: String) -> Self {
+        Self {
+            line: Line::styled(text, Style::new().bold()),
+            link: None,
+        }
+    }
+
+    fn text(text: String) -> Self {
+        Self {
+            line: Line::from(text),
+            link: None,
+        }
+    }
+
+    fn link(text: String, item_ref: ItemRef) -> Self {
+        Self {
+            line: Line::from(text),
+            link: Some(item_ref),
+        }
+    }
+}
+
+struct App<'a> {
+    db: &'a DB,
+    focus: Focus,
+    query: String,
+    results: Vec<ItemRef>,
+    result_state: ListState,
+    item: Option<ItemRef>,
+    /// Items we've navigated away from, for going back.
+    history: Vec<ItemRef>,
+    lines: Vec<DetailLine>,
+    line_state: ListState,
+    quit: bool,
+}
+
+fn amount_line(db: &DB, indent: &str, amount: &Amount) -> DetailLine {
+    let text = format!("{}{}", indent, amount);
+    match amount.id.and_then(|id| db.new_item_ref(id).ok()) {
+        Some(item_ref) => DetailLine::link(text, item_ref),
+        None => DetailLine::text(text),
+    }
+}
+
+fn own_process_lines(db: &DB, lines: &mut Vec<DetailLine>, process: &Process) {
+    lines.push(DetailLine::text(format!(
+        "  {}, {}s",
+        process.location(),
+        process.time
+    )));
+    for input in &process.inputs {
+        lines.push(amount_line(db, "    in:  ", input));
+    }
+    for output in &process.outputs {
+        lines.push(amount_line(db, "    out: ", output));
+    }
+}
+
+fn referencing_lines(db: &DB, lines: &mut Vec<DetailLine>, title: &str, processes: &[Process]) {
+    lines.push(DetailLine::heading(format!(
+        "{} ({})",
+        title,
+        processes.len()
+    )));
+    for process in processes {
+        let item_ref = db.new_item_ref(process.item.id).unwrap();
+        lines.push(DetailLine::link(
+            format!(
+                "  {}: {} -> {}",
+                process.item.name,
+                join(&process.inputs),
+                join(&process.outputs)
+            ),
+            item_ref,
+        ));
+    }
+    lines.push(DetailLine::text(String::new()));
+}
+
+impl<'a> App<'a> {
+    fn new(db: &'a DB) -> Self {
+        let mut app = Self {
+            db,
+            focus: Focus::Search,
+            query: String::new(),
+            results: vec![],
+            result_state: ListState::default(),
+            item: None,
+            history: vec![],
+            lines: vec![],
+            line_state: ListState::default(),
+            quit: false,
+        };
+        app.search();
+        app
+    }
+
+    fn search(&mut self) {
+        self.results = if self.query.is_empty() {
+            vec![]
+        } else {
+            self.db
+                .search(&self.query)
+                .into_iter()
+                .map(|result| result.item_ref)
+                .collect()
+        };
+        self.result_state
+            .select((!self.results.is_empty()).then_some(0));
+    }
+
+    fn open(&mut self, item_ref: ItemRef) {
+        if let Some(current) = self.item.replace(item_ref) {
+            if current != item_ref {
+                self.history.push(current);
+            }
+        }
+        self.show_item();
+    }
+
+    fn back(&mut self) {
+        if let Some(previous) = self.history.pop() {
+            self.item = Some(previous);
+            self.show_item();
+        }
+    }
+
+    fn show_item(&mut self) {
+        let Some(item_ref) = self.item else {
+            return;
+        };
+        let db = self.db;
+        let mut lines = vec![];
+
+        let (fabricate, deconstruct) = processes(db, item_ref);
+        lines.push(DetailLine::heading(format!(
+            "Fabricate ({})",
+            fabricate.0.len()
+        )));
+        for process in &fabricate.0 {
+            own_process_lines(db, &mut lines, process);
+        }
+        lines.push(DetailLine::text(String::new()));
+        lines.push(DetailLine::heading(format!(
+            "Deconstruct ({})",
+            deconstruct.0.len()
+        )));
+        for process in &deconstruct.0 {
+            own_process_lines(db, &mut lines, process);
+        }
+        lines.push(DetailLine::text(String::new()));
+
+        let used_by = referencing_processes(db, db.get_used_by(item_ref));
+        referencing_lines(db, &mut lines, "Used by", &used_by.0);
+        let produced_by = referencing_processes(db, db.get_produced_by(item_ref));
+        referencing_lines(db, &mut lines, "Produced by", &produced_by.0);
+
+        self.lines = lines;
+        self.line_state = ListState::default();
+        self.line_state
+            .select(self.lines.iter().position(|line| line.link.is_some()));
+        self.focus = Focus::Item;
+    }
+
+    fn move_link(&mut self, forward: bool) {
+        let current = self.line_state.selected();
+        let links = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.link.is_some())
+            .map(|(idx, _)| idx);
+        let next = if forward {
+            links
+                .clone()
+                .find(|idx| current.map_or(true, |current| *idx > current))
+        } else {
+            links
+                .rev()
+                .find(|idx| current.map_or(true, |current| *idx < current))
+        };
+        if next.is_some() {
+            self.line_state.select(next);
+        }
+    }
+
+    fn move_result(&mut self, forward: bool) {
+        if self.results.is_empty() {
+            return;
+        }
+        let current = self.result_state.selected().unwrap_or(0);
+        let next = if forward {
+            (current + 1).min(self.results.len() - 1)
+        } else {
+            current.saturating_sub(1)
+        };
+        self.result_state.select(Some(next));
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+
+        match (self.focus, code) {
+            (_, KeyCode::Tab) => {
+                self.focus = match self.focus {
+                    Focus::Search if self.item.is_some() => Focus::Item,
+                    _ => Focus::Search,
+                }
+            }
+            (Focus::Search, KeyCode::Char(c)) => {
+                self.query.push(c);
+                self.search();
+            }
+            (Focus::Search, KeyCode::Backspace) => {
+                self.query.pop();
+                self.search();
+            }
+            (Focus::Search, KeyCode::Up) => self.move_result(false),
+            (Focus::Search, KeyCode::Down) => self.move_result(true),
+            (Focus::Search, KeyCode::Enter) => {
+                if let Some(idx) = self.result_state.selected() {
+                    self.open(self.results[idx]);
+                }
+            }
+            (Focus::Search, KeyCode::Esc) if self.item.is_some() => self.focus = Focus::Item,
+            // Nothing to go back to, and q is just another letter here.
+            (Focus::Search, KeyCode::Esc) => self.quit = true,
+            (Focus::Item, KeyCode::Char('/')) => self.focus = Focus::Search,
+            (Focus::Item, KeyCode::Char('q') | KeyCode::Esc) => self.quit = true,
+            (Focus::Item, KeyCode::Up) => self.move_link(false),
+            (Focus::Item, KeyCode::Down) => self.move_link(true),
+            (Focus::Item, KeyCode::Enter) => {
+                let link = self
+                    .line_state
+                    .selected()
+                    .and_then(|idx| self.lines[idx].link);
+                if let Some(item_ref) = link {
+                    self.open(item_ref);
+                }
+            }
+            (Focus::Item, KeyCode::Backspace) => self.back(),
+            _ => {}
+        }
+    }
+
+    fn border_style(&self, focus: Focus) -> Style {
+        if self.focus == focus {
+            Style::new().yellow()
+        } else {
+            Style::new()
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let columns = Layout::new(
+            Direction::Horizontal,
+            [Constraint::Percentage(30), Constraint::Min(0)],
+        )
+        .split(frame.size());
+        let (left, right) = (columns[0], columns[1]);
+        let rows = Layout::new(
+            Direction::Vertical,
+            [Constraint::Length(3), Constraint::Min(0)],
+        )
+        .split(left);
+        let (search, results) = (rows[0], rows[1]);
+
+        let search_box = Paragraph::new(self.query.as_str()).block(
+            bordered()
+                .title("Search")
+                .border_style(self.border_style(Focus::Search)),
+        );
+        frame.render_widget(search_box, search);
+        if self.focus == Focus::Search {
+            frame.set_cursor(
+                search.x + 1 + self.query.chars().count() as u16,
+                search.y + 1,
+            );
+        }
+
+        let result_items = self
+            .results
+            .iter()
+            .map(|item_ref| ListItem::new(ItemSummary::new(self.db, *item_ref).name.to_owned()))
+            .collect::<Vec<_>>();
+        let result_list = List::new(result_items)
+            .block(bordered().title(format!("Results ({})", self.results.len())))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(result_list, results, &mut self.result_state);
+
+        self.draw_item(frame, right);
+    }
+
+    fn draw_item(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(item_ref) = self.item else {
+            let help = Paragraph::new(vec![
+                Line::from("Type to search, Enter to open an item."),
+                Line::from("Tab switches panes, Backspace goes back, Esc quits."),
+            ])
+            .block(bordered().title("Barohead"));
+            frame.render_widget(help, area);
+            return;
+        };
+
+        let db = self.db;
+        let item = db.get_item(item_ref).unwrap();
+        let title = Line::from(vec![
+            Span::from(db.item_translations.get_name(item_ref).unwrap().to_owned()).bold(),
+            Span::from(format!(" ({})", item.id)),
+        ]);
+        let block = bordered()
+            .title(title)
+            .border_style(self.border_style(Focus::Item));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let price_height = if item.price.is_some() {
+            INTERESTING_MERCHANTS.len() as u16 + 1
+        } else {
+            0
+        };
+        let rows = Layout::new(
+            Direction::Vertical,
+            [Constraint::Min(0), Constraint::Length(price_height)],
+        )
+        .split(inner);
+        let (details, prices) = (rows[0], rows[1]);
+
+        let link_style = Style::new().cyan();
+        let lines = self
+            .lines
+            .iter()
+            .map(|line| {
+                let mut text = line.line.clone();
+                if line.link.is_some() {
+                    text.patch_style(link_style);
+                }
+                ListItem::new(text)
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(lines).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, details, &mut self.line_state);
+
+        if let Some(price) = &item.price {
+            let rows = Prices::new(db, price, &INTERESTING_MERCHANTS)
+                .0
+                .into_iter()
+                .map(|store| {
+                    Row::new(vec![
+                        store.name.to_owned(),
+                        store
+                            .purchase_price
+                            .map_or_else(|| "-".to_owned(), |price| format!("{} mk", price)),
+                        format!("{} mk", store.sale_price),
+                    ])
+                })
+                .collect::<Vec<_>>();
+            let table = Table::new(
+                rows,
+                [
+                    Constraint::Length(24),
+                    Constraint::Length(10),
+                    Constraint::Length(10),
+                ],
+            )
+            .header(Row::new(vec!["Store", "Buy for", "Sell for"]).bold());
+            frame.render_widget(table, prices);
+        }
+    }
+}
+
+fn bordered() -> Block<'static> {
+    Block::default().borders(Borders::ALL)
+}
+
+type CrosstermTerminal = Terminal<CrosstermBackend<Stdout>>;
+
+fn run_app(terminal: &mut CrosstermTerminal, db: &DB) -> io::Result<()> {
+    let mut app = App::new(db);
+    while !app.quit {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key.code, key.modifiers);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn restore() -> io::Result<()> {
+    disable_raw_mode()?;
+    io::stdout()
+        .execute(LeaveAlternateScreen)?
+        .execute(crossterm::cursor::Show)?;
+    Ok(())
+}
+
+pub fn run(db: &DB) -> io::Result<()> {
+    // Put the terminal back before a panic message gets printed into it.
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore();
+        hook(info);
+    }));
+
+    enable_raw_mode()?;
+    io::stdout().execute(EnterAlternateScreen)?;
+    let result = Terminal::new(CrosstermBackend::new(io::stdout()))
+        .and_then(|mut terminal| run_app(&mut terminal, db));
+    restore()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use barohead_db::test_support::{fabricate, item, itemdb};
+
+    use super::*;
+
+    fn fixture() -> DB {
+        let mut wire = item("wire");
+        wire.fabricate.push(fabricate(&[("copper", 1)], 2));
+        DB::new(itemdb(
+            vec![item("copper"), wire],
+            &[
+                ("entityname.copper", "Copper"),
+                ("entityname.wire", "Copper Wire"),
+            ],
+        ))
+        .unwrap()
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(code, KeyModifiers::NONE);
+    }
+
+    fn type_query(app: &mut App, query: &str) {
+        for c in query.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn moves_through_results() {
+        let db = fixture();
+        let mut app = App::new(&db);
+        app.move_result(true);
+        assert_eq!(app.result_state.selected(), None);
+
+        type_query(&mut app, "cop");
+        assert_eq!(app.results.len(), 2);
+        assert_eq!(app.result_state.selected(), Some(0));
+        app.move_result(true);
+        app.move_result(true);
+        assert_eq!(app.result_state.selected(), Some(1));
+        app.move_result(false);
+        app.move_result(false);
+        assert_eq!(app.result_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn switches_panes_and_follows_links() {
+        let db = fixture();
+        let copper = db.new_item_ref("copper").unwrap();
+        let wire = db.new_item_ref("wire").unwrap();
+        let mut app = App::new(&db);
+
+        // Letters, q and / included, are all part of the search.
+        type_query(&mut app, "q/");
+        assert_eq!(app.query, "q/");
+        assert!(!app.quit);
+        // Nothing to switch to yet.
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.focus, Focus::Search);
+
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Backspace);
+        type_query(&mut app, "copper");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!((app.item, app.focus), (Some(copper), Focus::Item));
+
+        // Copper's first link is the wire it's used in.
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.item, Some(wire));
+        press(&mut app, KeyCode::Backspace);
+        assert_eq!(app.item, Some(copper));
+
+        press(&mut app, KeyCode::Char('/'));
+        assert_eq!(app.focus, Focus::Search);
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.focus, Focus::Item);
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn ctrl_c_always_quits() {
+        let db = fixture();
+        let mut app = App::new(&db);
+        app.handle_key(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert!(app.quit);
+        assert!(app.query.is_empty());
+    }
+}
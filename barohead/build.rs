@@ -0,0 +1,25 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+// The item database is fetched at runtime rather than embedded, so the url it
+// is fetched from needs to change whenever the contents do.
+fn main() {
+    println!("cargo:rerun-if-changed=recipes.bincode");
+
+    let bytes = std::fs::read("recipes.bincode")
+        .expect("recipes.bincode is missing, generate it with regen-index.sh");
+
+    let mut hasher = DefaultHasher::new();
+    hasher.write(&bytes);
+    println!("cargo:rustc-env=ITEMDB_HASH={:016x}", hasher.finish());
+
+    // The icon atlas is generated alongside the database, and index.html
+    // always ships it.
+    println!("cargo:rerun-if-changed=icons.png");
+    let bytes =
+        std::fs::read("icons.png").expect("icons.png is missing, generate it with regen-index.sh");
+
+    let mut hasher = DefaultHasher::new();
+    hasher.write(&bytes);
+    println!("cargo:rustc-env=ICON_ATLAS_HASH={:016x}", hasher.finish());
+}
@@ -1,13 +1,24 @@
 use std::rc::Rc;
 
+use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
+use yew_router::history::{AnyHistory, History, MemoryHistory};
 use yew_router::prelude::*;
 
-use barohead_data::items::*;
+use barohead_data::{
+    database,
+    items::{Category, StoreIdentifier},
+};
+use barohead_db::{Campaign, ModFilter, DB, INTERESTING_MERCHANTS};
 
 use crate::{
-    components::{ItemView, Nav},
-    db::DB,
+    campaign::{self, CampaignContext},
+    components::{
+        CategoryView, CombineView, CoverageReport, GraphExplorer, HomeView, ItemView, LoopsReport,
+        ModsView, Nav, PlanView, SalvageReport, StoreView, TagView,
+    },
+    loader::{fetch_item_db, Progress},
+    mod_filter::{self, ModFilterContext},
     routes::Route,
 };
 
@@ -28,6 +39,74 @@ fn item_page(ItemPageProps { id }: &ItemPageProps) -> Html {
     }
 }
 
+#[function_component(ItemGraphPage)]
+fn item_graph_page(ItemPageProps { id }: &ItemPageProps) -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let item_ref = db.new_item_ref(id).expect("Loading item data");
+    // Keyed so that expanded nodes don't carry over to another item.
+    html! {
+        <>
+            <Nav />
+            <GraphExplorer key={id.as_str()} {item_ref} />
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct TagPageProps {
+    tag: AttrValue,
+}
+
+#[function_component(TagPage)]
+fn tag_page(TagPageProps { tag }: &TagPageProps) -> Html {
+    html! {
+        <>
+            <Nav />
+            <TagView tag={tag} />
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct StorePageProps {
+    id: AttrValue,
+}
+
+#[function_component(StorePage)]
+fn store_page(StorePageProps { id }: &StorePageProps) -> Html {
+    let store = StoreIdentifier::from_internal_name(id)
+        .filter(|store| INTERESTING_MERCHANTS.contains(store));
+    html! {
+        <>
+            <Nav />
+            if let Some(store) = store {
+                <StoreView {store} />
+            } else {
+                <div class="container">{"Unknown store"}</div>
+            }
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct CategoryPageProps {
+    name: AttrValue,
+}
+
+#[function_component(CategoryPage)]
+fn category_page(CategoryPageProps { name }: &CategoryPageProps) -> Html {
+    html! {
+        <>
+            <Nav />
+            if let Some(category) = Category::from_internal_name(name) {
+                <CategoryView {category} />
+            } else {
+                <div class="container">{"Unknown category"}</div>
+            }
+        </>
+    }
+}
+
 fn switch(route: Route) -> Html {
     match route {
         Route::Home => html! { <Home /> },
@@ -36,33 +115,142 @@ fn switch(route: Route) -> Html {
                <ItemPage id={id} />
             }
         }
+        Route::ItemGraph { id } => html! { <ItemGraphPage id={id} /> },
+        Route::Tag { tag } => html! { <TagPage tag={tag} /> },
+        Route::Store { id } => html! { <StorePage id={id} /> },
+        Route::Category { name } => html! { <CategoryPage name={name} /> },
+        Route::Combine => html! { <><Nav /><CombineView /></> },
+        Route::Plan => html! { <><Nav /><PlanView /></> },
+        Route::Loops => html! { <><Nav /><LoopsReport /></> },
+        Route::Coverage => html! { <><Nav /><CoverageReport /></> },
+        Route::Salvage => html! { <><Nav /><SalvageReport /></> },
+        Route::Mods => html! { <><Nav /><ModsView /></> },
     }
 }
 
 #[function_component(Home)]
 fn home() -> Html {
-    html! { <Nav /> }
+    html! { <><Nav /><HomeView /></> }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct StaticAppProps {
+    pub db: Rc<DB>,
+    /// The full path of the page, including any base path.
+    pub path: AttrValue,
+    #[prop_or_default]
+    pub basename: Option<AttrValue>,
+}
+
+/// The app with an already loaded database, rendered at a fixed location.
+/// This is what gets used for prerendering.
+#[function_component(StaticApp)]
+pub fn static_app(StaticAppProps { db, path, basename }: &StaticAppProps) -> Html {
+    let history = AnyHistory::from(MemoryHistory::new());
+    history.push(path.as_str());
+    // Prerendered pages can't know anyone's campaign or mod filter.
+    let campaign = use_state(Campaign::default);
+    let mod_filter = use_state(ModFilter::default);
+    html! {
+        <ContextProvider<Rc<DB>> context={db.clone()}>
+            <ContextProvider<CampaignContext> context={campaign}>
+                <ContextProvider<ModFilterContext> context={mod_filter}>
+                    <Router {history} basename={basename.clone()}>
+                        <Switch<Route> render={switch} />
+                    </Router>
+                </ContextProvider<ModFilterContext>>
+            </ContextProvider<CampaignContext>>
+        </ContextProvider<Rc<DB>>>
+    }
+}
+
+#[derive(PartialEq)]
+enum LoadState {
+    Loading(Progress),
+    Failed(String),
+    Loaded(Rc<DB>),
+}
+
+#[derive(Properties, PartialEq)]
+struct LoadingProps {
+    progress: Progress,
+}
+
+#[function_component(Loading)]
+fn loading(LoadingProps { progress }: &LoadingProps) -> Html {
+    let kib = |bytes: usize| format!("{} KiB", bytes / 1024);
+    html! {
+        <section class="section">
+            <div class="container">
+                <p>{"Loading item database..."}</p>
+                if let Some(total) = progress.total {
+                    <progress class="progress" value={progress.loaded.min(total).to_string()} max={total.to_string()} />
+                    <p>{kib(progress.loaded)} {" / "} {kib(total)}</p>
+                } else {
+                    <progress class="progress" />
+                    <p>{kib(progress.loaded)}</p>
+                }
+            </div>
+        </section>
+    }
+}
+
+fn decode_item_db(bytes: &[u8]) -> Result<DB, String> {
+    let (_header, item_data) = database::read(bytes)
+        .map_err(|err| format!("Unable to read the item database: {}", err))?;
+    DB::new(item_data).map_err(|err| format!("Unable to index the item database: {}", err))
 }
 
 #[function_component(App)]
 pub fn app() -> Html {
-    // It might be easier to make this a lazy_static, but then we don't have the
-    // option of having different databases for different versions.
-    let db = use_memo(
-        |_| {
-            let items_bincode = std::include_bytes!("../recipes.bincode");
-            let item_data: ItemDB = bincode::deserialize(items_bincode).unwrap();
-            DB::from(item_data)
+    // The database is loaded at runtime rather than being compiled in, which
+    // leaves the option of having different databases for different versions.
+    let load_state = use_state(|| LoadState::Loading(Progress::default()));
+    let campaign = use_state(campaign::load);
+    let mod_filter = use_state(mod_filter::load);
+    {
+        let load_state = load_state.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    let on_progress = {
+                        let load_state = load_state.clone();
+                        move |progress| load_state.set(LoadState::Loading(progress))
+                    };
+                    let result = fetch_item_db(on_progress)
+                        .await
+                        .map_err(|err| format!("Failed to load item database: {}", err))
+                        .and_then(|bytes| decode_item_db(&bytes));
+                    load_state.set(match result {
+                        Ok(db) => LoadState::Loaded(Rc::new(db)),
+                        Err(err) => LoadState::Failed(err),
+                    });
+                });
+                || ()
+            },
+            (),
+        );
+    }
+
+    match &*load_state {
+        LoadState::Loading(progress) => html! { <Loading progress={*progress} /> },
+        LoadState::Failed(err) => html! {
+            <section class="section">
+                <div class="container">
+                    <div class="notification is-danger">{err}</div>
+                </div>
+            </section>
         },
-        (),
-    );
-    html! {
-        <>
-            <ContextProvider<Rc<DB>> context={db}>
-                <BrowserRouter>
-                    <Switch<Route> render={switch} />
-                </BrowserRouter>
+        LoadState::Loaded(db) => html! {
+            <ContextProvider<Rc<DB>> context={db.clone()}>
+                <ContextProvider<CampaignContext> context={campaign.clone()}>
+                    <ContextProvider<ModFilterContext> context={mod_filter.clone()}>
+                        <BrowserRouter>
+                            <Switch<Route> render={switch} />
+                        </BrowserRouter>
+                    </ContextProvider<ModFilterContext>>
+                </ContextProvider<CampaignContext>>
             </ContextProvider<Rc<DB>>>
-        </>
+        },
     }
 }
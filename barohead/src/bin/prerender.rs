@@ -0,0 +1,319 @@
+// Render a static page for every item, tag and store, so that links to
+// barohead have a title and a preview before any wasm has loaded. Run it over
+// the output of `trunk build`:
+//
+//   prerender --db recipes.bincode --template dist/index.html --out dist \
+//     --site-url https://example.com/barohead
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::rc::Rc;
+
+use barohead_data::{
+    database,
+    items::{Category, ItemRef as DataItemRef},
+};
+use yew::LocalServerRenderer;
+
+use barohead::app::{StaticApp, StaticAppProps};
+use barohead_db::{ItemRef, StoreSummary, DB, INTERESTING_MERCHANTS};
+
+struct Args {
+    db: PathBuf,
+    template: PathBuf,
+    out: PathBuf,
+    site_url: String,
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: prerender --db FILE --template FILE --out DIR --site-url URL");
+    exit(1);
+}
+
+fn parse_args() -> Args {
+    let mut db = None;
+    let mut template = None;
+    let mut out = None;
+    let mut site_url = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--db" => db = Some(value.into()),
+            "--template" => template = Some(value.into()),
+            "--out" => out = Some(value.into()),
+            "--site-url" => site_url = Some(value.trim_end_matches('/').to_owned()),
+            _ => usage(),
+        }
+    }
+    match (db, template, out, site_url) {
+        (Some(db), Some(template), Some(out), Some(site_url)) => Args {
+            db,
+            template,
+            out,
+            site_url,
+        },
+        _ => usage(),
+    }
+}
+
+struct Page {
+    /// Relative to the site root, without leading or trailing slashes.
+    path: String,
+    title: String,
+    description: String,
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn item_name(db: &DB, id: &str) -> String {
+    db.new_item_ref(id)
+        .map(|item_ref| db.item_translations.get_name(item_ref).unwrap().to_owned())
+        .unwrap_or_else(|_| id.to_owned())
+}
+
+fn describe_amounts(items: impl Iterator<Item = (String, i32)>) -> String {
+    items
+        .map(|(name, amount)| {
+            if amount == 1 {
+                name
+            } else {
+                format!("{}x {}", amount, name)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn describe_item(db: &DB, item_ref: ItemRef) -> String {
+    let item = db.get_item(item_ref).unwrap();
+    let mut sentences = vec![];
+
+    if let Some(fabricate) = item.fabricate.first() {
+        let inputs = fabricate.required_items.iter().map(|required_item| {
+            let name = match &required_item.item {
+                DataItemRef::Id(id) => item_name(db, id),
+                DataItemRef::Tag(tag) => format!("any {}", tag),
+            };
+            (name, required_item.amount)
+        });
+        sentences.push(format!("Fabricated from {}.", describe_amounts(inputs)));
+    }
+
+    if let Some(deconstruct) = item.deconstruct.first() {
+        let outputs = deconstruct
+            .items
+            .iter()
+            .map(|produced_item| (item_name(db, &produced_item.id), produced_item.amount));
+        sentences.push(format!("Deconstructs into {}.", describe_amounts(outputs)));
+    }
+
+    if let Some(price) = &item.price {
+        let cheapest = INTERESTING_MERCHANTS
+            .iter()
+            .filter_map(|store| StoreSummary::for_store(price, *store).sell)
+            .min();
+        if let Some(cheapest) = cheapest {
+            sentences.push(format!("Sold from {} mk.", cheapest));
+        }
+    }
+
+    if let Some(used_by) = db.get_used_by(item_ref) {
+        sentences.push(format!("Used by {} recipes.", used_by.len()));
+    }
+
+    sentences.join(" ")
+}
+
+fn collect_pages(db: &DB) -> Vec<Page> {
+    let mut pages = vec![Page {
+        path: String::new(),
+        title: "Barohead".to_owned(),
+        description: "Recipes, deconstruction and prices for Barotrauma items.".to_owned(),
+    }];
+
+    pages.extend(db.item_refs().map(|item_ref| {
+        let item = db.get_item(item_ref).unwrap();
+        Page {
+            path: format!("item/{}", item.id),
+            title: format!(
+                "{} - Barohead",
+                db.item_translations.get_name(item_ref).unwrap()
+            ),
+            description: describe_item(db, item_ref),
+        }
+    }));
+
+    pages.extend(db.tags().map(|tag| {
+        let used_by = db.get_tag_used_by(tag).map(|used_by| used_by.len());
+        Page {
+            path: format!("tag/{}", tag),
+            title: format!("Tag: {} - Barohead", tag),
+            description: format!(
+                "{} recipes accept any item tagged {}.",
+                used_by.unwrap_or(0),
+                tag
+            ),
+        }
+    }));
+
+    pages.extend(
+        Category::ALL
+            .into_iter()
+            .map(|category| (category, db.items_in_category(category).len()))
+            .filter(|(_, count)| *count > 0)
+            .map(|(category, count)| Page {
+                path: format!("category/{}", category.internal_name()),
+                title: format!("{} - Barohead", category.name()),
+                description: format!("{} items in the {} category.", count, category.name()),
+            }),
+    );
+
+    pages.extend(INTERESTING_MERCHANTS.iter().map(|store| {
+        let stock = db
+            .item_refs()
+            .filter_map(|item_ref| db.get_item(item_ref).unwrap().price.as_ref())
+            .filter(|price| StoreSummary::for_store(price, *store).sell.is_some())
+            .count();
+        let name = db.store_translations.get_name(store).unwrap();
+        Page {
+            path: format!("store/{}", store.internal_name()),
+            title: format!("{} - Barohead", name),
+            description: format!("{} items for sale at {}.", stock, name),
+        }
+    }));
+
+    pages
+}
+
+fn page_url(site_url: &str, page: &Page) -> String {
+    if page.path.is_empty() {
+        format!("{}/", site_url)
+    } else {
+        format!("{}/{}", site_url, page.path)
+    }
+}
+
+fn inject(template: &str, site_url: &str, page: &Page, body: &str) -> String {
+    let head = format!(
+        concat!(
+            "<title>{title}</title>\n",
+            "        <meta name=\"description\" content=\"{description}\"/>\n",
+            "        <meta property=\"og:type\" content=\"website\"/>\n",
+            "        <meta property=\"og:site_name\" content=\"Barohead\"/>\n",
+            "        <meta property=\"og:title\" content=\"{title}\"/>\n",
+            "        <meta property=\"og:description\" content=\"{description}\"/>\n",
+            "        <meta property=\"og:url\" content=\"{url}\"/>",
+        ),
+        title = escape(&page.title),
+        description = escape(&page.description),
+        url = escape(&page_url(site_url, page)),
+    );
+
+    let html = template.replacen("<title>Barohead</title>", &head, 1);
+
+    let body_start = html
+        .find("<body")
+        .and_then(|start| html[start..].find('>').map(|end| start + end + 1))
+        .expect("Template has no <body>");
+    format!(
+        "{}<div id=\"prerendered\">{}</div>{}",
+        &html[..body_start],
+        body,
+        &html[body_start..]
+    )
+}
+
+fn write_page(out: &Path, page: &Page, html: &str) {
+    let dir = out.join(&page.path);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("index.html"), html).unwrap();
+}
+
+fn sitemap(site_url: &str, pages: &[Page]) -> String {
+    let urls = pages
+        .iter()
+        .map(|page| {
+            format!(
+                "  <url><loc>{}</loc></url>\n",
+                escape(&page_url(site_url, page))
+            )
+        })
+        .collect::<String>();
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+            "{}",
+            "</urlset>\n",
+        ),
+        urls
+    )
+}
+
+fn main() {
+    let args = parse_args();
+
+    let bytes = fs::read(&args.db).unwrap();
+    let (_header, itemdb) = database::read(&bytes).unwrap_or_else(|err| {
+        eprintln!("{}: {}", args.db.display(), err);
+        exit(1);
+    });
+    let db = DB::new(itemdb).unwrap_or_else(|err| {
+        eprintln!("{}: {}", args.db.display(), err);
+        exit(1);
+    });
+    let db = Rc::new(db);
+
+    let template = fs::read_to_string(&args.template).unwrap();
+    if template.contains("id=\"prerendered\"") {
+        eprintln!("Template has already been prerendered, rebuild it with trunk");
+        exit(1);
+    }
+
+    // The path part of the site url, which the router needs to know about to
+    // produce correct links.
+    let basename = args
+        .site_url
+        .split_once("://")
+        .and_then(|(_, rest)| rest.find('/').map(|idx| rest[idx..].to_owned()))
+        .unwrap_or_default();
+
+    let pages = collect_pages(&db);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let local = tokio::task::LocalSet::new();
+    local.block_on(&runtime, async {
+        for page in &pages {
+            let props = StaticAppProps {
+                db: db.clone(),
+                path: format!("{}/{}", basename, page.path).into(),
+                basename: (!basename.is_empty()).then(|| basename.clone().into()),
+            };
+            // The app renders from scratch once loaded rather than hydrating, so
+            // leave out the hydration markers.
+            let body = LocalServerRenderer::<StaticApp>::with_props(props)
+                .hydratable(false)
+                .render()
+                .await;
+            let html = inject(&template, &args.site_url, page, &body);
+            write_page(&args.out, page, &html);
+        }
+    });
+
+    fs::write(
+        args.out.join("sitemap.xml"),
+        sitemap(&args.site_url, &pages),
+    )
+    .unwrap();
+    eprintln!("Prerendered {} pages", pages.len());
+}
@@ -0,0 +1,51 @@
+use yew::UseStateHandle;
+
+use barohead_db::Campaign;
+
+const STORAGE_KEY: &str = "barohead.campaign";
+
+/// The campaign prices are shown for, shared by every page and changed from
+/// the campaign settings panel.
+pub type CampaignContext = UseStateHandle<Campaign>;
+
+/// The settings saved by [`save`], or the defaults.
+pub fn load() -> Campaign {
+    let saved = web_sys::window()
+        .and_then(|window| window.local_storage().ok()?)
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok()?);
+    saved
+        .and_then(|saved| {
+            let mut parts = saved.split('&');
+            let location_reputation = parts.next()?.parse().ok()?;
+            // Empty where no faction runs the location.
+            let faction_reputation = match parts.next()? {
+                "" => None,
+                reputation => Some(reputation.parse().ok()?),
+            };
+            let store_price_multiplier = parts.next()?.parse().ok()?;
+            Some(Campaign {
+                faction_reputation,
+                location_reputation,
+                store_price_multiplier,
+            })
+        })
+        .unwrap_or_default()
+}
+
+pub fn save(campaign: &Campaign) {
+    if let Some(storage) = web_sys::window().and_then(|window| window.local_storage().ok()?) {
+        // As with plans, failing to save only means starting over next time.
+        let _ = storage.set_item(
+            STORAGE_KEY,
+            &format!(
+                "{}&{}&{}",
+                campaign.location_reputation,
+                campaign
+                    .faction_reputation
+                    .map(|reputation| reputation.to_string())
+                    .unwrap_or_default(),
+                campaign.store_price_multiplier
+            ),
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use barohead_db::Campaign;
+
+use crate::campaign::{self, CampaignContext};
+
+fn input_value(e: &Event) -> Option<f32> {
+    let input: HtmlInputElement = e.target()?.unchecked_into();
+    input.value().parse().ok()
+}
+
+fn reputation_slider(value: f32, onchange: Callback<Event>) -> Html {
+    html! {
+        <input
+            type="range"
+            class="reputation-slider"
+            min={Campaign::MIN_REPUTATION.to_string()}
+            max={Campaign::MAX_REPUTATION.to_string()}
+            step="1"
+            value={value.to_string()}
+            {onchange}
+        />
+    }
+}
+
+/// Where to change the campaign that prices are shown for. The settings are
+/// shared by every page and remembered in this browser.
+#[function_component(CampaignSettings)]
+pub fn campaign_settings() -> Html {
+    let campaign = use_context::<CampaignContext>().unwrap();
+
+    let update = {
+        let campaign = campaign.clone();
+        move |change: &dyn Fn(&mut Campaign)| {
+            let mut updated = *campaign;
+            change(&mut updated);
+            campaign::save(&updated);
+            campaign.set(updated);
+        }
+    };
+
+    let on_location_reputation = {
+        let update = update.clone();
+        Callback::from(move |e: Event| {
+            if let Some(reputation) = input_value(&e) {
+                update(&|campaign| campaign.location_reputation = reputation);
+            }
+        })
+    };
+
+    let on_faction_reputation = {
+        let update = update.clone();
+        Callback::from(move |e: Event| {
+            if let Some(reputation) = input_value(&e) {
+                update(&|campaign| campaign.faction_reputation = Some(reputation));
+            }
+        })
+    };
+
+    let on_faction = {
+        let update = update.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target().unwrap().unchecked_into();
+            let faction_reputation = input.checked().then_some(0.0);
+            update(&|campaign| campaign.faction_reputation = faction_reputation);
+        })
+    };
+
+    let on_multiplier = {
+        let update = update.clone();
+        Callback::from(move |e: Event| {
+            if let Some(multiplier) = input_value(&e).filter(|multiplier| *multiplier > 0.0) {
+                update(&|campaign| campaign.store_price_multiplier = multiplier);
+            }
+        })
+    };
+
+    let on_reset = Callback::from(move |_| update(&|campaign| *campaign = Campaign::default()));
+
+    let summary = if *campaign == Campaign::default() {
+        "Campaign settings: neutral reputation, normal prices".to_owned()
+    } else {
+        format!(
+            "Campaign settings: reputation {}, store prices ×{}",
+            campaign.reputation(),
+            campaign.store_price_multiplier
+        )
+    };
+
+    html! {
+        <details class="campaign-settings no-print">
+            <summary>{summary}</summary>
+            <div class="field">
+                <label class="checkbox">
+                    <input
+                        type="checkbox"
+                        checked={campaign.faction_reputation.is_some()}
+                        onchange={on_faction}
+                    />
+                    {" A faction runs the location"}
+                </label>
+            </div>
+            if let Some(faction_reputation) = campaign.faction_reputation {
+                <div class="field">
+                    <label class="label">{format!("Reputation with the location's faction: {}", faction_reputation)}</label>
+                    <div class="control">
+                        {reputation_slider(faction_reputation, on_faction_reputation)}
+                    </div>
+                    <p class="help">{"Merchants go by this rather than the location's own reputation."}</p>
+                </div>
+            } else {
+                <div class="field">
+                    <label class="label">{format!("Reputation with the location: {}", campaign.location_reputation)}</label>
+                    <div class="control">
+                        {reputation_slider(campaign.location_reputation, on_location_reputation)}
+                    </div>
+                </div>
+            }
+            <p class="help">{"Good reputation takes up to 10% off what merchants charge and adds as much to what they pay."}</p>
+            <div class="field">
+                <label class="label">{"Store price multiplier"}</label>
+                <div class="control">
+                    <input
+                        type="number"
+                        class="input"
+                        min="0.1"
+                        step="0.1"
+                        value={campaign.store_price_multiplier.to_string()}
+                        onchange={on_multiplier}
+                    />
+                </div>
+                <p class="help">{"From the campaign's settings. It changes what merchants charge, not what they pay."}</p>
+            </div>
+            <button class="button is-small" onclick={on_reset}>{"Reset"}</button>
+        </details>
+    }
+}
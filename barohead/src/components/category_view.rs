@@ -0,0 +1,124 @@
+use std::cmp::Reverse;
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+use barohead_data::items::Category;
+use barohead_db::{ItemRef, DB};
+
+use crate::components::sortable_table::{sort_rows, sortable_table, SortColumn};
+use crate::components::ItemThumbnail;
+use crate::mod_filter::{self, ModFilterContext};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Column {
+    Name,
+    Id,
+    Price,
+    Fabricated,
+    Deconstructed,
+    UsedBy,
+}
+
+impl SortColumn<Row> for Column {
+    const ALL: &'static [Column] = &[
+        Column::Name,
+        Column::Id,
+        Column::Price,
+        Column::Fabricated,
+        Column::Deconstructed,
+        Column::UsedBy,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Column::Name => "Name",
+            Column::Id => "Id",
+            Column::Price => "Base Price",
+            Column::Fabricated => "Recipes",
+            Column::Deconstructed => "Deconstructs",
+            Column::UsedBy => "Used By",
+        }
+    }
+
+    fn sort(self, rows: &mut [Row]) {
+        match self {
+            Column::Name => {}
+            Column::Id => rows.sort_by(|a, b| a.id.cmp(&b.id)),
+            Column::Price => rows.sort_by_key(|row| Reverse(row.price)),
+            Column::Fabricated => rows.sort_by_key(|row| Reverse(row.fabricated)),
+            Column::Deconstructed => rows.sort_by_key(|row| Reverse(row.deconstructed)),
+            Column::UsedBy => rows.sort_by_key(|row| Reverse(row.used_by)),
+        }
+    }
+}
+
+// Everything that's shown and sorted on, worked out once per item.
+struct Row {
+    item_ref: ItemRef,
+    id: String,
+    price: Option<i32>,
+    fabricated: usize,
+    deconstructed: bool,
+    used_by: usize,
+}
+
+impl Row {
+    fn new(db: &DB, item_ref: ItemRef) -> Self {
+        let item = db.get_item(item_ref).unwrap();
+        Row {
+            item_ref,
+            id: item.id.clone(),
+            price: item.price.as_ref().map(|price| price.baseprice),
+            fabricated: item.fabricate.len(),
+            deconstructed: !item.deconstruct.is_empty(),
+            used_by: db.get_used_by(item_ref).map_or(0, |used_by| used_by.len()),
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub category: Category,
+}
+
+#[function_component(CategoryView)]
+pub fn category_view(Props { category }: &Props) -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let filter = use_context::<ModFilterContext>().unwrap();
+    let sort = use_state(|| (Column::Name, false));
+
+    let mut rows = db
+        .items_in_category(*category)
+        .into_iter()
+        .filter(|item_ref| mod_filter::shows(&db, &filter, *item_ref))
+        .map(|item_ref| Row::new(&db, item_ref))
+        .collect::<Vec<_>>();
+    sort_rows(&mut rows, *sort);
+
+    let body = rows
+        .iter()
+        .map(|row| {
+            html! {
+                <tr>
+                    <td><ItemThumbnail item_ref={row.item_ref} link=true /></td>
+                    <td class="item-id">{&row.id}</td>
+                    <td>{row.price.map(|price| format!("{} mk", price)).unwrap_or_default()}</td>
+                    <td>{row.fabricated}</td>
+                    <td>{if row.deconstructed { "Yes" } else { "" }}</td>
+                    <td>{row.used_by}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{category.name()}</h1>
+                <p>{format!("{} items.", rows.len())}</p>
+            </div>
+            {sortable_table(&sort, body)}
+        </div>
+    }
+}
@@ -0,0 +1,116 @@
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use barohead_db::{Node, DB};
+
+use crate::components::{ItemSearch, ShowProcess};
+
+#[function_component(CombineView)]
+pub fn combine_view() -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let wanted = use_state(Vec::<Node>::new);
+
+    let add = {
+        let wanted = wanted.clone();
+        Callback::from(move |node: Node| {
+            if !wanted.contains(&node) {
+                let mut next = (*wanted).clone();
+                next.push(node);
+                wanted.set(next);
+            }
+        })
+    };
+    let on_item = add.reform(Node::Item);
+    let on_tag = add.reform(|e: Event| {
+        let select: HtmlSelectElement = e.target().unwrap().unchecked_into();
+        let tag = select.value();
+        select.set_value("");
+        Node::Tag(tag)
+    });
+
+    let chips = wanted
+        .iter()
+        .enumerate()
+        .map(|(idx, node)| {
+            let label = match node {
+                Node::Item(item_ref) => db.item_translations.get_name(item_ref).unwrap().to_owned(),
+                Node::Tag(tag) => format!("any {}", tag),
+            };
+            let onclick = {
+                let wanted = wanted.clone();
+                Callback::from(move |_| {
+                    let mut next = (*wanted).clone();
+                    next.remove(idx);
+                    wanted.set(next);
+                })
+            };
+            html! {
+                <span class="tag is-medium">
+                    {label}
+                    <button class="delete is-small" {onclick}></button>
+                </span>
+            }
+        })
+        .collect::<Html>();
+
+    let tag_options = db
+        .tags()
+        .map(|tag| html! { <option value={tag.to_owned()}>{tag}</option> })
+        .collect::<Html>();
+
+    let combinations = db.processes_using_all(&wanted).unwrap();
+    let results = combinations
+        .iter()
+        .map(|combination| {
+            let process_ref = combination.process.clone();
+            html! {
+                <>
+                    <div class="panel-block has-text-weight-semibold">
+                        {format!(
+                            "{} of {} ingredients",
+                            combination.covered, combination.ingredients
+                        )}
+                    </div>
+                    <ShowProcess {process_ref} />
+                </>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{"What Uses All Of These?"}</h1>
+                <p>
+                    {"Add items and tags to find every recipe that takes all of them, the ones needing the least else first. "}
+                    {"A tag only matches recipes that ask for the tag itself."}
+                </p>
+            </div>
+            <div class="box">
+                <div class="field is-grouped">
+                    <div class="control">
+                        <ItemSearch onselect={on_item} />
+                    </div>
+                    <div class="control">
+                        <div class="select">
+                            <select onchange={on_tag}>
+                                <option value="" selected=true disabled=true>{"Add a tag"}</option>
+                                {tag_options}
+                            </select>
+                        </div>
+                    </div>
+                </div>
+                <div class="tags">{chips}</div>
+            </div>
+            if !wanted.is_empty() {
+                <div class="panel">
+                    <div class="panel-heading">{format!("Recipes ({})", combinations.len())}</div>
+                    {results}
+                </div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,47 @@
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// The fraction of its condition an item has left.
+    pub condition: f32,
+    pub onchange: Callback<f32>,
+}
+
+/// Picks how worn an item is, for working out what it's worth.
+#[function_component(ConditionSlider)]
+pub fn condition_slider(
+    Props {
+        condition,
+        onchange,
+    }: &Props,
+) -> Html {
+    let percent = (condition * 100.0).round() as i32;
+
+    let on_input = {
+        let onchange = onchange.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target().unwrap().unchecked_into();
+            if let Ok(percent) = input.value().parse::<f32>() {
+                onchange.emit(percent / 100.0);
+            }
+        })
+    };
+
+    html! {
+        <div class="field condition-slider no-print">
+            <label class="label">{format!("Condition: {}%", percent)}</label>
+            <div class="control">
+                <input
+                    type="range"
+                    min="0"
+                    max="100"
+                    step="5"
+                    value={percent.to_string()}
+                    oninput={on_input}
+                />
+            </div>
+        </div>
+    }
+}
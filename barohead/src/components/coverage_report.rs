@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+use barohead_data::items::Category;
+use barohead_db::{ItemRef, StoreAvailability, DB};
+
+use crate::components::ItemThumbnail;
+use crate::mod_filter::{self, ModFilterContext};
+
+// Items in several categories are listed under the first, and ones in none
+// come last.
+fn grouped(db: &DB, title: &str, items: Vec<ItemRef>) -> Html {
+    let mut groups: BTreeMap<(StoreAvailability, Option<Category>), Vec<ItemRef>> = BTreeMap::new();
+    for item_ref in items {
+        let availability = db.store_availability(item_ref).unwrap();
+        let category = db.get_item(item_ref).unwrap().categories.first().copied();
+        groups
+            .entry((availability, category))
+            .or_default()
+            .push(item_ref);
+    }
+    let total = groups.values().map(Vec::len).sum::<usize>();
+
+    let mut groups = groups.into_iter().collect::<Vec<_>>();
+    groups.sort_by_key(|((availability, category), _)| (*availability, category.is_none()));
+
+    let groups = groups
+        .into_iter()
+        .map(|((availability, category), mut items)| {
+            let heading = format!(
+                "{} · {} ({})",
+                availability.name(),
+                category.map_or("Uncategorized", Category::name),
+                items.len()
+            );
+            items.sort_by_key(|item_ref| db.item_translations.get_name(item_ref).unwrap());
+            let items = items
+                .into_iter()
+                .map(|item_ref| html! { <ItemThumbnail {item_ref} link=true /> })
+                .collect::<Html>();
+            html! {
+                <>
+                    <div class="panel-block has-text-weight-semibold">{heading}</div>
+                    <div class="panel-block item-grid">{items}</div>
+                </>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="panel">
+            <div class="panel-heading">{format!("{} ({})", title, total)}</div>
+            {groups}
+        </div>
+    }
+}
+
+#[function_component(CoverageReport)]
+pub fn coverage_report() -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let filter = use_context::<ModFilterContext>().unwrap();
+    let shown = |mut items: Vec<ItemRef>| {
+        items.retain(|item_ref| mod_filter::shows(&db, &filter, *item_ref));
+        items
+    };
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{"Unobtainable and Unused Items"}</h1>
+                <p>
+                    {"Items with no known source are neither fabricated, dropped by deconstructing anything, nor sold by any merchant a campaign can visit. \
+                      Unused items aren't an ingredient in any recipe, whether by name or by one of their tags. \
+                      Either can point at gaps in the extracted data or at mistakes in a mod."}
+                </p>
+            </div>
+            {grouped(&db, "No Known Source", shown(db.unobtainable_items().unwrap()))}
+            {grouped(&db, "Never Used", shown(db.unused_items().unwrap()))}
+        </div>
+    }
+}
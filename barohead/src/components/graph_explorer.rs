@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use barohead_data::items::Fabricator;
+use barohead_db::{Edge, ItemRef, Node, ProcessRef, RecipeGraph, DB};
+
+use crate::routes::Route;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub item_ref: ItemRef,
+}
+
+const NODE_WIDTH: i32 = 170;
+const NODE_HEIGHT: i32 = 28;
+const COLUMN_WIDTH: i32 = NODE_WIDTH + 110;
+const ROW_HEIGHT: i32 = NODE_HEIGHT + 12;
+const MARGIN: i32 = 10;
+/// Columns longer than this are folded down to it until opened, or common
+/// materials would push everything else off screen.
+const MAX_ROWS: usize = 12;
+
+#[derive(PartialEq, Clone)]
+struct Filters {
+    fabricate: bool,
+    deconstruct: bool,
+    fabricators: BTreeSet<Fabricator>,
+}
+
+impl Default for Filters {
+    fn default() -> Self {
+        Self {
+            fabricate: true,
+            deconstruct: true,
+            fabricators: Fabricator::ALL.into_iter().collect(),
+        }
+    }
+}
+
+impl Filters {
+    fn keep(&self, db: &DB, edge: &Edge) -> bool {
+        match &edge.process {
+            ProcessRef::Fabricate(fabricate_ref) => {
+                let fabricators = &db
+                    .get_fabricate(fabricate_ref)
+                    .unwrap()
+                    .suitable_fabricators;
+                self.fabricate
+                    && (fabricators.is_empty()
+                        || fabricators
+                            .iter()
+                            .any(|fabricator| self.fabricators.contains(fabricator)))
+            }
+            ProcessRef::Deconstruct(_) => self.deconstruct,
+        }
+    }
+}
+
+fn node_label(db: &DB, node: &Node) -> String {
+    let label = match node {
+        Node::Item(item_ref) => db.item_translations.get_name(item_ref).unwrap().to_owned(),
+        Node::Tag(tag) => format!("any {}", tag),
+    };
+    if label.chars().count() > 22 {
+        format!("{}…", label.chars().take(21).collect::<String>())
+    } else {
+        label
+    }
+}
+
+fn toggle(expanded: &UseStateHandle<BTreeSet<ItemRef>>, item_ref: ItemRef) {
+    let mut next = (**expanded).clone();
+    if !next.remove(&item_ref) {
+        next.insert(item_ref);
+    }
+    expanded.set(next);
+}
+
+fn checkbox(label: &'static str, checked: bool, onchange: Callback<bool>) -> Html {
+    let onchange = Callback::from(move |e: Event| {
+        let input: HtmlInputElement = e.target().unwrap().unchecked_into();
+        onchange.emit(input.checked());
+    });
+    html! {
+        <label class="checkbox mr-4">
+            <input type="checkbox" {checked} {onchange} />
+            {" "}{label}
+        </label>
+    }
+}
+
+#[function_component(GraphExplorer)]
+pub fn graph_explorer(Props { item_ref }: &Props) -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let navigator = use_navigator().unwrap();
+    let expanded = use_state(BTreeSet::<ItemRef>::new);
+    // Layers the user has asked to see in full.
+    let unfolded = use_state(BTreeSet::<i32>::new);
+    let filters = use_state(Filters::default);
+
+    // The neighbourhood of the centre, plus that of every expanded item.
+    let mut graph = RecipeGraph::around(&db, *item_ref, 1, 1).unwrap();
+    for expanded_ref in expanded.iter() {
+        graph.merge(RecipeGraph::around(&db, *expanded_ref, 1, 1).unwrap());
+    }
+    graph.edges.retain(|edge| filters.keep(&db, edge));
+
+    let center = Node::Item(*item_ref);
+    let layers = graph.layers(&center);
+
+    let mut columns: BTreeMap<i32, Vec<(String, &Node)>> = BTreeMap::new();
+    for (node, layer) in &layers {
+        columns
+            .entry(*layer)
+            .or_default()
+            .push((node_label(&db, node), node));
+    }
+    let first_layer = columns.keys().next().copied().unwrap_or(0);
+    let mut positions = BTreeMap::new();
+    // How many nodes each folded layer is hiding.
+    let mut folded = BTreeMap::new();
+    for (layer, nodes) in columns.iter_mut() {
+        nodes.sort();
+        if nodes.len() > MAX_ROWS && !unfolded.contains(layer) {
+            // The centre and expanded items stay, so what was clicked on
+            // doesn't vanish.
+            let (mut kept, rest): (Vec<_>, Vec<_>) = nodes.drain(..).partition(|(_, node)| {
+                **node == center
+                    || matches!(node, Node::Item(node_ref) if expanded.contains(node_ref))
+            });
+            let room = (MAX_ROWS - 1).saturating_sub(kept.len()).min(rest.len());
+            folded.insert(*layer, rest.len() - room);
+            kept.extend(rest.into_iter().take(room));
+            kept.sort();
+            *nodes = kept;
+        }
+        for (row, (_, node)) in nodes.iter().enumerate() {
+            let x = MARGIN + (layer - first_layer) * COLUMN_WIDTH;
+            let y = MARGIN + row as i32 * ROW_HEIGHT;
+            positions.insert((*node).clone(), (x, y));
+        }
+    }
+    let width = MARGIN * 2 + columns.len() as i32 * COLUMN_WIDTH - (COLUMN_WIDTH - NODE_WIDTH);
+    let height = MARGIN * 2
+        + columns
+            .iter()
+            .map(|(layer, nodes)| nodes.len() as i32 + folded.contains_key(layer) as i32)
+            .max()
+            .unwrap_or(1)
+            * ROW_HEIGHT;
+
+    let edges = graph
+        .edges
+        .iter()
+        .filter_map(|edge| {
+            let (x1, y1) = positions.get(&edge.from)?;
+            let (x2, y2) = positions.get(&edge.to)?;
+            let (y1, y2) = (y1 + NODE_HEIGHT / 2, y2 + NODE_HEIGHT / 2);
+            // Edges usually run left to right, but ones that loop back (say
+            // deconstructing into an ingredient) go between the near sides
+            // and bow downwards.
+            let (x1, x2, bend, dip) = if x2 > x1 {
+                (x1 + NODE_WIDTH, *x2, 60, 0)
+            } else {
+                (*x1, x2 + NODE_WIDTH, -60, NODE_HEIGHT)
+            };
+            let path = format!(
+                "M {} {} C {} {}, {} {}, {} {}",
+                x1,
+                y1,
+                x1 + bend,
+                y1 + dip,
+                x2 - bend,
+                y2 + dip,
+                x2,
+                y2
+            );
+            let class = match edge.process {
+                ProcessRef::Fabricate(_) => "edge fabricate",
+                ProcessRef::Deconstruct(_) => "edge deconstruct",
+            };
+            Some(html! {
+                <g class={class}>
+                    <path d={path} marker-end="url(#arrow)" />
+                    <text x={((x1 + x2) / 2).to_string()} y={((y1 + y2) / 2 - 3).to_string()}>
+                        {edge.label()}
+                    </text>
+                </g>
+            })
+        })
+        .collect::<Html>();
+
+    let nodes = positions
+        .iter()
+        .map(|(node, (x, y))| {
+            let label = node_label(&db, node);
+            let mut classes = classes!("node");
+            if *node == center {
+                classes.push("center");
+            }
+            let (onclick, ondblclick, marker) = match node {
+                Node::Item(node_ref) => {
+                    let node_ref = *node_ref;
+                    let is_expanded = expanded.contains(&node_ref);
+                    if is_expanded {
+                        classes.push("expanded");
+                    }
+                    // A double-click also fires two clicks first. Only the
+                    // first gets through, and the double-click undoes it.
+                    let onclick = {
+                        let expanded = expanded.clone();
+                        Callback::from(move |e: MouseEvent| {
+                            if e.detail() <= 1 {
+                                toggle(&expanded, node_ref);
+                            }
+                        })
+                    };
+                    let ondblclick = {
+                        let expanded = expanded.clone();
+                        let navigator = navigator.clone();
+                        let id = db.get_item(node_ref).unwrap().id.clone();
+                        Callback::from(move |_| {
+                            toggle(&expanded, node_ref);
+                            navigator.push(&Route::ItemGraph { id: id.clone() })
+                        })
+                    };
+                    let marker = if node_ref == *item_ref {
+                        ""
+                    } else if is_expanded {
+                        "−"
+                    } else {
+                        "+"
+                    };
+                    (Some(onclick), Some(ondblclick), marker)
+                }
+                Node::Tag(_) => {
+                    classes.push("tag");
+                    (None, None, "")
+                }
+            };
+            html! {
+                <g class={classes} transform={format!("translate({} {})", x, y)} {onclick} {ondblclick}>
+                    <title>{&label}</title>
+                    <rect width={NODE_WIDTH.to_string()} height={NODE_HEIGHT.to_string()} rx="4" />
+                    <text x="8" y={(NODE_HEIGHT / 2 + 5).to_string()}>{label}</text>
+                    <text class="marker" x={(NODE_WIDTH - 14).to_string()} y={(NODE_HEIGHT / 2 + 5).to_string()}>{marker}</text>
+                </g>
+            }
+        })
+        .collect::<Html>();
+
+    let more = folded
+        .iter()
+        .map(|(layer, hidden)| {
+            let layer = *layer;
+            let x = MARGIN + (layer - first_layer) * COLUMN_WIDTH;
+            let y = MARGIN + columns[&layer].len() as i32 * ROW_HEIGHT;
+            let onclick = {
+                let unfolded = unfolded.clone();
+                Callback::from(move |_| {
+                    let mut next = (*unfolded).clone();
+                    next.insert(layer);
+                    unfolded.set(next);
+                })
+            };
+            html! {
+                <g class="node more" transform={format!("translate({} {})", x, y)} {onclick}>
+                    <rect width={NODE_WIDTH.to_string()} height={NODE_HEIGHT.to_string()} rx="4" />
+                    <text x="8" y={(NODE_HEIGHT / 2 + 5).to_string()}>{format!("{} more…", hidden)}</text>
+                </g>
+            }
+        })
+        .collect::<Html>();
+
+    let filter_checkbox = |label, checked, update: fn(&mut Filters, bool)| {
+        let filters = filters.clone();
+        checkbox(
+            label,
+            checked,
+            Callback::from(move |checked| {
+                let mut next = (*filters).clone();
+                update(&mut next, checked);
+                filters.set(next);
+            }),
+        )
+    };
+    let fabricator_checkboxes = Fabricator::ALL
+        .into_iter()
+        .map(|fabricator| {
+            let label = fabricator.name();
+            let filters = filters.clone();
+            checkbox(
+                label,
+                filters.fabricators.contains(&fabricator),
+                Callback::from(move |checked| {
+                    let mut next = (*filters).clone();
+                    if checked {
+                        next.fabricators.insert(fabricator.clone());
+                    } else {
+                        next.fabricators.remove(&fabricator);
+                    }
+                    filters.set(next);
+                }),
+            )
+        })
+        .collect::<Html>();
+
+    let name = db.item_translations.get_name(item_ref).unwrap();
+    let item_route = Route::Item {
+        id: db.get_item(*item_ref).unwrap().id.clone(),
+    };
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{format!("Recipe Graph: {}", name)}</h1>
+                <p>
+                    {"Ingredients on the left, what it's made into on the right. Click an item to expand it, double-click to centre on it. Long columns are cut short until you click the last entry. "}
+                    <Link<Route> to={item_route}>{format!("Back to {}", name)}</Link<Route>>
+                </p>
+            </div>
+            <div class="box">
+                <div class="field">
+                    {filter_checkbox("Fabricate", filters.fabricate, |filters, checked| filters.fabricate = checked)}
+                    {filter_checkbox("Deconstruct", filters.deconstruct, |filters, checked| filters.deconstruct = checked)}
+                </div>
+                <div class="field">
+                    {fabricator_checkboxes}
+                </div>
+            </div>
+            <div class="recipe-graph">
+                <svg width={width.to_string()} height={height.to_string()}>
+                    <defs>
+                        <marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto">
+                            <path d="M 0 0 L 10 5 L 0 10 z" />
+                        </marker>
+                    </defs>
+                    {edges}
+                    {nodes}
+                    {more}
+                </svg>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,95 @@
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use barohead_db::{GraphFormat, ItemRef, RecipeGraph, DB};
+
+use crate::download::download;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub item_ref: ItemRef,
+}
+
+const DEPTHS: [usize; 4] = [1, 2, 3, 5];
+
+fn format_label(format: GraphFormat) -> &'static str {
+    match format {
+        GraphFormat::Dot => "DOT",
+        GraphFormat::Mermaid => "Mermaid",
+        GraphFormat::GraphMl => "GraphML",
+    }
+}
+
+#[function_component(GraphExport)]
+pub fn graph_export(Props { item_ref }: &Props) -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let depth = use_state(|| 1);
+
+    let on_depth = {
+        let depth = depth.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target().unwrap().unchecked_into();
+            // "0" is the whole database.
+            depth.set(select.value().parse().unwrap_or(1));
+        })
+    };
+
+    let buttons = GraphFormat::ALL
+        .into_iter()
+        .map(|format| {
+            let db = db.clone();
+            let item_ref = *item_ref;
+            let depth = *depth;
+            let onclick = Callback::from(move |_| {
+                let (graph, filename) = if depth == 0 {
+                    (RecipeGraph::all(&db), "barohead".to_owned())
+                } else {
+                    let id = &db.get_item(item_ref).unwrap().id;
+                    (
+                        RecipeGraph::around(&db, item_ref, depth, depth),
+                        format!("{}-{}", id, depth),
+                    )
+                };
+                let contents = graph.unwrap().render(&db, format).unwrap();
+                let filename = format!("{}.{}", filename, format.extension());
+                download(&filename, format.mime_type(), &contents).unwrap();
+            });
+            html! {
+                <div class="control">
+                    <button class="button" {onclick}>{format_label(format)}</button>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    let options = DEPTHS
+        .iter()
+        .map(|option| {
+            let label = if *option == 1 {
+                "1 step each way".to_owned()
+            } else {
+                format!("{} steps each way", option)
+            };
+            html! {
+                <option value={option.to_string()} selected={*depth == *option}>{label}</option>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="field is-grouped">
+            <div class="control">
+                <div class="select">
+                    <select onchange={on_depth}>
+                        {options}
+                        <option value="0" selected={*depth == 0}>{"Whole database"}</option>
+                    </select>
+                </div>
+            </div>
+            {buttons}
+        </div>
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use barohead_data::items::Category;
+use barohead_db::{ItemRef, DB};
+
+use crate::mod_filter::{self, ModFilterContext};
+use crate::routes::Route;
+
+// Items grouped by the first letter of their name, for scanning by eye.
+fn alphabetical_index(db: &DB, items: &[ItemRef]) -> Html {
+    let mut letters: BTreeMap<char, Vec<ItemRef>> = BTreeMap::new();
+    for item_ref in items {
+        let name = db.item_translations.get_name(item_ref).unwrap();
+        let letter = name
+            .chars()
+            .next()
+            .map(|letter| letter.to_ascii_uppercase())
+            .unwrap_or('?');
+        letters.entry(letter).or_default().push(*item_ref);
+    }
+
+    letters
+        .into_iter()
+        .map(|(letter, items)| {
+            let links = items
+                .into_iter()
+                .map(|item_ref| {
+                    let id = db.get_item(item_ref).unwrap().id.clone();
+                    html! {
+                        <li>
+                            <Link<Route> to={Route::Item { id }}>
+                                {db.item_translations.get_name(item_ref).unwrap()}
+                            </Link<Route>>
+                        </li>
+                    }
+                })
+                .collect::<Html>();
+            html! {
+                <div class="index-letter">
+                    <strong>{letter}</strong>
+                    <ul>{links}</ul>
+                </div>
+            }
+        })
+        .collect()
+}
+
+#[function_component(HomeView)]
+pub fn home_view() -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let filter = use_context::<ModFilterContext>().unwrap();
+
+    let categories = Category::ALL
+        .into_iter()
+        .map(|category| {
+            let mut items = db.items_in_category(category);
+            items.retain(|item_ref| mod_filter::shows(&db, &filter, *item_ref));
+            (category, items)
+        })
+        .filter(|(_, items)| !items.is_empty())
+        .collect::<Vec<_>>();
+
+    let tiles = categories
+        .iter()
+        .map(|(category, items)| {
+            html! {
+                <div class="column is-one-quarter-desktop is-one-third-tablet">
+                    <Link<Route> to={Route::Category { name: category.internal_name().to_owned() }} classes="box category-tile">
+                        <p class="title is-5">{category.name()}</p>
+                        <p class="subtitle is-6">{format!("{} items", items.len())}</p>
+                    </Link<Route>>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    let indexes = categories
+        .iter()
+        .map(|(category, items)| {
+            html! {
+                <details class="panel">
+                    <summary class="panel-heading">{format!("{} ({})", category.name(), items.len())}</summary>
+                    <div class="panel-block alphabetical-index">{alphabetical_index(&db, items)}</div>
+                </details>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{"Browse Items"}</h1>
+                <p>{"Pick a category to see everything in it, or open one of the indexes below. An item can be in more than one category."}</p>
+            </div>
+            if categories.is_empty() {
+                <div class="notification">
+                    {"This item database has no categories. Rebuild it with extract-content to browse by category, or use the search above."}
+                </div>
+            }
+            <div class="columns is-multiline">{tiles}</div>
+            {indexes}
+        </div>
+    }
+}
@@ -6,12 +6,19 @@ use yew_autocomplete::{view::Bulma, Autocomplete, ItemResolver, ItemResolverResu
 use yew_commons::FnProp;
 use yew_router::prelude::*;
 
-use crate::db::{SearchResult, DB};
+use barohead_db::{ItemRef, SearchResult, DB};
+
+use crate::mod_filter::{self, ModFilterContext};
 use crate::routes::Route;
 
-impl RenderHtml for SearchResult {
+// The autocomplete needs to know how to render results, which we can only
+// teach it about our own types.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SearchHit(SearchResult);
+
+impl RenderHtml for SearchHit {
     fn render(&self) -> Html {
-        html! { <ShowSearchResult search_result={self.clone()} /> }
+        html! { <ShowSearchResult search_result={self.0.clone()} /> }
     }
 }
 
@@ -26,8 +33,11 @@ fn show_search_result(Props { search_result }: &Props) -> Html {
     let mut peekable = search_result.indices.iter().peekable();
 
     // TODO: this is _really_ slow, you can feel the difference. It should use chunks.
-    let item = db.get_item(search_result.item_ref);
-    let description = db.item_translations.get_name(search_result.item_ref);
+    let item = db.get_item(search_result.item_ref).unwrap();
+    let description = db
+        .item_translations
+        .get_name(search_result.item_ref)
+        .unwrap();
     let visible_match = description
         .char_indices()
         .map(|(idx, ch)| match peekable.peek() {
@@ -54,37 +64,56 @@ fn show_search_result(Props { search_result }: &Props) -> Html {
     }
 }
 
+#[derive(Properties, PartialEq)]
+pub struct ItemSearchProps {
+    /// Called with the chosen item. Without it, choosing an item goes to its
+    /// page.
+    #[prop_or_default]
+    pub onselect: Option<Callback<ItemRef>>,
+}
+
 #[function_component(ItemSearch)]
-pub fn item_search() -> Html {
+pub fn item_search(ItemSearchProps { onselect }: &ItemSearchProps) -> Html {
     let db = use_context::<Rc<DB>>().unwrap();
+    let filter = use_context::<ModFilterContext>().unwrap();
     let navigator = use_navigator().unwrap();
 
     let navigate_to_item = {
         let db = db.clone();
-        Callback::from(move |items: Vec<SearchResult>| {
-            let item_ref = &items.first().unwrap().item_ref;
-            let item = db.get_item(*item_ref);
+        let onselect = onselect.clone();
+        Callback::from(move |items: Vec<SearchHit>| {
+            let item_ref = items.first().unwrap().0.item_ref;
+            if let Some(onselect) = &onselect {
+                onselect.emit(item_ref);
+                return;
+            }
+            let item = db.get_item(item_ref).unwrap();
             navigator.push(&Route::Item {
                 id: item.id.clone(),
             })
         })
     };
 
-    let resolve_items: ItemResolver<SearchResult> = {
-        FnProp::from(move |guess: String| -> ItemResolverResult<SearchResult> {
-            let names = db.search(guess.as_str());
+    let resolve_items: ItemResolver<SearchHit> = {
+        FnProp::from(move |guess: String| -> ItemResolverResult<SearchHit> {
+            let names = db
+                .search(guess.as_str())
+                .into_iter()
+                .filter(|result| mod_filter::shows(&db, &filter, result.item_ref))
+                .map(SearchHit)
+                .collect();
             Box::pin(async { Ok(names) })
         })
     };
 
     html! {
-        <Autocomplete<SearchResult>
+        <Autocomplete<SearchHit>
             {resolve_items}
             onchange={navigate_to_item}
             auto=true
         >
-            <Bulma<SearchResult>
+            <Bulma<SearchHit>
         />
-        </Autocomplete<SearchResult>>
+        </Autocomplete<SearchHit>>
     }
 }
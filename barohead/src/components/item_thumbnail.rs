@@ -3,10 +3,28 @@ use std::rc::Rc;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-use barohead_data::items::ConditionRange;
+use barohead_data::items::{ConditionRange, IconAtlas, Rect};
 
-use crate::db::{ItemRef, DB};
+use crate::loader::ICON_ATLAS_URL;
 use crate::routes::Route;
+use barohead_db::{ItemRef, DB};
+
+// pack-index draws icons at twice the size they're shown, for high density
+// screens.
+const ICON_SCALE: u32 = 2;
+
+fn icon_style(atlas: &IconAtlas, rect: Rect) -> String {
+    format!(
+        "width: {}px; height: {}px; background-image: url({}); background-position: -{}px -{}px; background-size: {}px {}px",
+        rect.width / ICON_SCALE,
+        rect.height / ICON_SCALE,
+        ICON_ATLAS_URL,
+        rect.x / ICON_SCALE,
+        rect.y / ICON_SCALE,
+        atlas.width / ICON_SCALE,
+        atlas.height / ICON_SCALE,
+    )
+}
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
@@ -33,15 +51,22 @@ pub fn item_thumbnail(
 ) -> Html {
     let db = use_context::<Rc<DB>>().unwrap();
 
-    let item = db.get_item(*item_ref);
+    let item = db.get_item(*item_ref).unwrap();
+    let icon = db
+        .icon_atlas()
+        .zip(item.atlas_rect)
+        .map(|(atlas, rect)| icon_style(atlas, rect));
 
     let body = html! {
         <>
+            if let Some(style) = icon {
+                <span class="icon-slot"><span class="item-icon" {style}></span></span>
+            }
             if amount.is_some() && amount.unwrap() != 1 {
               <span class="amount">{amount.unwrap()} {"x"}</span>
             }
             { " " }
-            <span class="name">{db.item_translations.get_name(item_ref)}</span>
+            <span class="name">{db.item_translations.get_name(item_ref).unwrap()}</span>
             if condition_range.is_some() {
                 <span class="condition-range">{format!("{:#?}", condition_range)}</span>
             }
@@ -2,11 +2,16 @@ use std::rc::Rc;
 
 use url_escape::encode_query;
 use yew::prelude::*;
+use yew_router::prelude::*;
 
-use crate::{
-    components::{PricingView, ShowDeconstruct, ShowFabricate, ShowProcess},
-    db::{DeconstructRef, FabricateRef, ItemRef, DB},
+use barohead_db::{DeconstructRef, FabricateRef, ItemRef, DB};
+
+use crate::components::{
+    ConditionSlider, GraphExport, ItemThumbnail, ModsQuery, PricingView, SalvageView,
+    ShowDeconstruct, ShowFabricate, ShowLoop, ShowProcess, ShowRichText,
 };
+use crate::plan::Plan;
+use crate::routes::Route;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
@@ -17,8 +22,16 @@ pub struct Props {
 pub fn item_view(Props { item_ref }: &Props) -> Html {
     let db = use_context::<Rc<DB>>().unwrap();
 
-    let item = db.get_item(*item_ref);
-    let name = db.item_translations.get_name(item_ref);
+    let item = db.get_item(*item_ref).unwrap();
+    let name = db.item_translations.get_name(item_ref).unwrap();
+
+    // How worn the item is, which changes what it sells for and what
+    // deconstructing it gives.
+    let condition = use_state(|| 1.0);
+    let on_condition = {
+        let condition = condition.clone();
+        Callback::from(move |value| condition.set(value))
+    };
 
     let fabricates = item
         .fabricate
@@ -45,7 +58,7 @@ pub fn item_view(Props { item_ref }: &Props) -> Html {
                 idx,
             };
             html! {
-                <ShowDeconstruct self_ref={*item_ref} {deconstruct_ref} />
+                <ShowDeconstruct self_ref={*item_ref} {deconstruct_ref} condition={*condition} />
             }
         })
         .collect::<Vec<_>>();
@@ -74,6 +87,49 @@ pub fn item_view(Props { item_ref }: &Props) -> Html {
             .collect::<Vec<_>>()
     });
 
+    let loops = db
+        .duplication_loops()
+        .unwrap()
+        .iter()
+        .enumerate()
+        .filter(|(_, found)| found.involves(*item_ref))
+        .map(|(idx, _)| html! { <ShowLoop {idx} /> })
+        .collect::<Vec<_>>();
+
+    let add_to_plan = {
+        let navigator = use_navigator().unwrap();
+        let id = item.id.clone();
+        Callback::from(move |_| {
+            let mut plan = Plan::load().unwrap_or_default();
+            plan.add(&id, 1);
+            plan.save();
+            navigator.push(&Route::Plan);
+        })
+    };
+
+    // Nothing to say about base game items unless something overrode them.
+    let provenance = db
+        .provenance(*item_ref)
+        .unwrap()
+        .filter(|provenance| db.is_modded(*item_ref).unwrap() || !provenance.overrides.is_empty());
+
+    let variant_base = db.variant_base(*item_ref).unwrap();
+    let variants = db
+        .variants(*item_ref)
+        .into_iter()
+        .map(|item_ref| html! { <ItemThumbnail {item_ref} link=true /> })
+        .collect::<Vec<_>>();
+
+    let tags = item
+        .tags
+        .iter()
+        .map(|tag| {
+            html! {
+                <Link<Route> to={Route::Tag { tag: tag.clone() }} classes="tag">{tag}</Link<Route>>
+            }
+        })
+        .collect::<Vec<_>>();
+
     let wiki_search_text = format!("Search for {name} on the Official Barotrauma Wiki");
     let wiki_search_url = format!(
         "https://barotraumagame.com/baro-wiki/index.php?search={}",
@@ -84,20 +140,75 @@ pub fn item_view(Props { item_ref }: &Props) -> Html {
         <div class="container">
             <div class="content">
                 <h1>{name}</h1>
+                if let Some(provenance) = provenance {
+                    <div class="tags">
+                        <Link<Route, ModsQuery> to={Route::Mods} query={Some(ModsQuery::only(&provenance.package))} classes="tag is-info">
+                            {format!("From {}", provenance.package)}
+                        </Link<Route, ModsQuery>>
+                        if !provenance.overrides.is_empty() {
+                            <span class="tag is-warning">
+                                {format!("Overrides {}", provenance.overrides.join(", "))}
+                            </span>
+                        }
+                        if !provenance.inherits.is_empty() {
+                            <span class="tag is-info is-light">
+                                {format!("Based on items from {}", provenance.inherits.join(", "))}
+                            </span>
+                        }
+                    </div>
+                }
+                if let Some(description) = db.item_description(*item_ref) {
+                    <ShowRichText text={description.clone()} />
+                }
                 <p>
                     <a href={wiki_search_url}>{wiki_search_text}</a>
                 </p>
+                <button class="button is-small" onclick={add_to_plan}>{"Add to plan"}</button>
             </div>
+            if !loops.is_empty() {
+                <div class="notification is-danger is-light">
+                    {format!("{} is part of {} resource duplication loop{}, listed below. ", name, loops.len(), if loops.len() == 1 { "" } else { "s" })}
+                    <Link<Route> to={Route::Loops}>{"See all loops"}</Link<Route>>
+                </div>
+            }
             <div class="panel">
                 <div class="panel-heading">{"Details"}</div>
                 <div class="panel-block">
                     <dl>
                         <dt>{"Id"}</dt>
                         <dd>{&item.id}</dd>
+                        if let Some(base) = variant_base {
+                            <dt>{"Variant of"}</dt>
+                            <dd><ItemThumbnail item_ref={base} link=true /></dd>
+                        }
+                        if !tags.is_empty() {
+                            <dt>{"Tags"}</dt>
+                            <dd><div class="tags">{tags}</div></dd>
+                        }
                     </dl>
                 </div>
+                if item.price.is_some() || !item.deconstruct.is_empty() {
+                    <div class="panel-block">
+                        <ConditionSlider condition={*condition} onchange={on_condition} />
+                    </div>
+                }
+                <div class="panel-block">
+                    <PricingView item_ref={*item_ref} condition={*condition} />
+                </div>
+            </div>
+            if !variants.is_empty() {
+                <div class="panel">
+                    <div class="panel-heading">{format!("Variants ({})", variants.len())}</div>
+                    <div class="panel-block item-grid">{variants}</div>
+                </div>
+            }
+            <div class="panel">
+                <div class="panel-heading">{"Recipe Graph"}</div>
                 <div class="panel-block">
-                    <PricingView item_ref={*item_ref} />
+                    <Link<Route> to={Route::ItemGraph { id: item.id.clone() }}>{"Explore the recipe graph"}</Link<Route>>
+                </div>
+                <div class="panel-block">
+                    <GraphExport item_ref={*item_ref} />
                 </div>
             </div>
             <div class="panel">
@@ -108,6 +219,7 @@ pub fn item_view(Props { item_ref }: &Props) -> Html {
                 <div class="panel-heading">{format!("Deconstructs Into ({})", deconstructs.len())}</div>
                 {deconstructs}
             </div>
+            <SalvageView item_ref={*item_ref} condition={*condition} />
             <div class="panel">
                 <div class="panel-heading">{format!("Used By ({})", used_by.as_ref().map(|ub|ub.len()).unwrap_or(0))}</div>
                 if used_by.is_some() {
@@ -120,6 +232,12 @@ pub fn item_view(Props { item_ref }: &Props) -> Html {
                     {produced_by.unwrap()}
                 }
             </div>
+            if !loops.is_empty() {
+                <div class="panel">
+                    <div class="panel-heading">{format!("Duplication Loops ({})", loops.len())}</div>
+                    {loops}
+                </div>
+            }
             <div class="panel">
                 <div class="panel-heading">{"Debug"}</div>
                 <details class="panel-block">
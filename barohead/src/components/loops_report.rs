@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+use barohead_db::{DB, MAX_LOOP_STEPS};
+
+use crate::components::ShowLoop;
+
+#[function_component(LoopsReport)]
+pub fn loops_report() -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let found = match db.duplication_loops() {
+        Ok(found) => found,
+        Err(err) => {
+            return html! {
+                <div class="container">
+                    <div class="notification is-danger">
+                        {format!("Unable to search for loops: {}", err)}
+                    </div>
+                </div>
+            }
+        }
+    };
+
+    // Outright exploits first, then the biggest gains.
+    let mut order = (0..found.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| {
+        let (a, b) = (&found[*a], &found[*b]);
+        b.is_free()
+            .cmp(&a.is_free())
+            .then(b.gain().total_cmp(&a.gain()))
+    });
+
+    let loops = order
+        .into_iter()
+        .map(|idx| html! { <ShowLoop {idx} /> })
+        .collect::<Vec<_>>();
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{"Resource Duplication Loops"}</h1>
+                <p>
+                    {format!(
+                        "Chains of up to {} fabrications and deconstructions that end with more of an item than they started with. \
+                         Each step runs as often as needed to use up what the previous one made.",
+                        MAX_LOOP_STEPS
+                    )}
+                </p>
+            </div>
+            <div class="panel">
+                <div class="panel-heading">{format!("Loops ({})", loops.len())}</div>
+                {loops}
+            </div>
+        </div>
+    }
+}
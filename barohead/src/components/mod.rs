@@ -1,17 +1,54 @@
+mod campaign_settings;
+mod category_view;
+mod combine_view;
+mod condition_slider;
+mod coverage_report;
+mod graph_explorer;
+mod graph_export;
+mod home_view;
 mod item_search;
 mod item_thumbnail;
 mod item_view;
+mod loops_report;
+mod mods_view;
 mod nav;
+mod plan_view;
 mod pricing;
+mod rich_text;
+mod salvage_report;
+mod salvage_view;
+mod schedule_view;
 mod show_deconstruct;
 mod show_fabricate;
+mod show_loop;
 mod show_process;
+mod sortable_table;
+mod store_view;
+mod tag_view;
 
+pub use campaign_settings::CampaignSettings;
+pub use category_view::CategoryView;
+pub use combine_view::CombineView;
+pub use condition_slider::ConditionSlider;
+pub use coverage_report::CoverageReport;
+pub use graph_explorer::GraphExplorer;
+pub use graph_export::GraphExport;
+pub use home_view::HomeView;
 pub use item_search::ItemSearch;
 pub use item_thumbnail::ItemThumbnail;
 pub use item_view::ItemView;
+pub use loops_report::LoopsReport;
+pub use mods_view::{ModsQuery, ModsView};
 pub use nav::Nav;
+pub use plan_view::PlanView;
 pub use pricing::PricingView;
+pub use rich_text::ShowRichText;
+pub use salvage_report::SalvageReport;
+pub use salvage_view::SalvageView;
+pub use schedule_view::ScheduleView;
 pub use show_deconstruct::ShowDeconstruct;
 pub use show_fabricate::ShowFabricate;
+pub use show_loop::ShowLoop;
 pub use show_process::ShowProcess;
+pub use store_view::StoreView;
+pub use tag_view::TagView;
@@ -0,0 +1,241 @@
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use barohead_db::{ItemRef, ModFilter, DB};
+
+use crate::components::ItemThumbnail;
+use crate::mod_filter::{self, ModFilterContext};
+use crate::routes::Route;
+
+/// A filter in the address, so a view of one mod can be linked to. Following
+/// the link sets the filter every page uses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ModsQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub only: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide: Option<String>,
+}
+
+impl ModsQuery {
+    pub fn only(package: &str) -> Self {
+        ModsQuery {
+            only: Some(package.to_owned()),
+            hide: None,
+        }
+    }
+
+    fn filter(&self) -> ModFilter {
+        match (&self.only, &self.hide) {
+            (Some(package), _) => ModFilter::Only(package.clone()),
+            (None, Some(package)) => ModFilter::Hide(package.clone()),
+            (None, None) => ModFilter::All,
+        }
+    }
+}
+
+fn sorted_by_name(db: &DB, mut items: Vec<ItemRef>) -> Vec<ItemRef> {
+    items.sort_by_key(|item_ref| db.item_translations.get_name(item_ref).unwrap());
+    items
+}
+
+#[function_component(ModsView)]
+pub fn mods_view() -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let location = use_location().unwrap();
+    let navigator = use_navigator().unwrap();
+
+    let mod_filter = use_context::<ModFilterContext>().unwrap();
+
+    let set_filter = {
+        let mod_filter = mod_filter.clone();
+        move |filter: ModFilter| {
+            mod_filter::save(&filter);
+            mod_filter.set(filter);
+        }
+    };
+
+    {
+        let query = location.query::<ModsQuery>().unwrap_or_default();
+        let set_filter = set_filter.clone();
+        use_effect_with_deps(
+            move |query| {
+                if *query != ModsQuery::default() {
+                    set_filter(query.filter());
+                    navigator.replace(&Route::Mods);
+                }
+                || ()
+            },
+            query,
+        );
+    }
+
+    let filter = (*mod_filter).clone();
+
+    if db.packages().is_empty() {
+        return html! {
+            <div class="container">
+                <div class="notification">
+                    {"This item database was built without content package information. Rebuild it with extract-content to see where items come from."}
+                </div>
+            </div>
+        };
+    }
+
+    let (mode, selected) = match &filter {
+        ModFilter::All => ("all", None),
+        ModFilter::Only(package) => ("only", Some(package)),
+        ModFilter::Hide(package) => ("hide", Some(package)),
+    };
+
+    let on_mode = {
+        let set_filter = set_filter.clone();
+        let package = selected.or(db.packages().first()).cloned();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target().unwrap().unchecked_into();
+            let package = package.clone().unwrap_or_default();
+            set_filter(match select.value().as_str() {
+                "only" => ModFilter::Only(package),
+                "hide" => ModFilter::Hide(package),
+                _ => ModFilter::All,
+            });
+        })
+    };
+
+    let on_package = {
+        let hiding = mode == "hide";
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target().unwrap().unchecked_into();
+            let package = select.value();
+            set_filter(if hiding {
+                ModFilter::Hide(package)
+            } else {
+                ModFilter::Only(package)
+            });
+        })
+    };
+
+    let packages = db
+        .packages()
+        .iter()
+        .map(|package| {
+            let items = db.filter_items(&ModFilter::Only(package.clone()));
+            let overrides = items
+                .iter()
+                .filter(|item_ref| {
+                    db.provenance(**item_ref)
+                        .unwrap()
+                        .is_some_and(|provenance| !provenance.overrides.is_empty())
+                })
+                .count();
+            html! {
+                <tr>
+                    <td>
+                        <Link<Route, ModsQuery> to={Route::Mods} query={Some(ModsQuery::only(package))}>
+                            {package}
+                        </Link<Route, ModsQuery>>
+                    </td>
+                    <td>{items.len()}</td>
+                    <td>{overrides}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    let conflicts = sorted_by_name(&db, db.overridden_items())
+        .into_iter()
+        .filter(|item_ref| filter.matches(db.provenance(*item_ref).unwrap()))
+        .map(|item_ref| {
+            let provenance = db.provenance(item_ref).unwrap().unwrap();
+            html! {
+                <tr>
+                    <td><ItemThumbnail {item_ref} link=true /></td>
+                    <td>{&provenance.package}</td>
+                    <td>{provenance.overrides.join(", ")}</td>
+                </tr>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let items = sorted_by_name(&db, db.filter_items(&filter))
+        .into_iter()
+        .map(|item_ref| html! { <ItemThumbnail {item_ref} link=true /> })
+        .collect::<Vec<_>>();
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{"Mods"}</h1>
+                <p>
+                    {"Content packages are applied in load order, and when more than one defines the same item the last one wins."}
+                </p>
+                <p>
+                    {"The filter below also applies to search and every other list of items, and is remembered in this browser."}
+                </p>
+            </div>
+            <div class="panel">
+                <div class="panel-heading">{format!("Content Packages ({})", db.packages().len())}</div>
+                <div class="panel-block">
+                    <table class="table">
+                        <thead>
+                            <tr>
+                                <th>{"Package"}</th>
+                                <th>{"Items"}</th>
+                                <th>{"Overrides"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>{packages}</tbody>
+                    </table>
+                </div>
+            </div>
+            <div class="panel">
+                <div class="panel-heading">{format!("Conflicts ({})", conflicts.len())}</div>
+                <div class="panel-block">
+                    <table class="table">
+                        <thead>
+                            <tr>
+                                <th>{"Item"}</th>
+                                <th>{"Winner"}</th>
+                                <th>{"Overrides"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>{conflicts}</tbody>
+                    </table>
+                </div>
+            </div>
+            <div class="panel">
+                <div class="panel-heading">{format!("Items ({})", items.len())}</div>
+                <div class="panel-block">
+                    <div class="field is-grouped">
+                        <div class="control">
+                            <div class="select">
+                                <select onchange={on_mode}>
+                                    <option value="all" selected={mode == "all"}>{"All packages"}</option>
+                                    <option value="only" selected={mode == "only"}>{"Only"}</option>
+                                    <option value="hide" selected={mode == "hide"}>{"Hide"}</option>
+                                </select>
+                            </div>
+                        </div>
+                        if let Some(selected) = selected {
+                            <div class="control">
+                                <div class="select">
+                                    <select onchange={on_package}>
+                                        {for db.packages().iter().map(|package| html! {
+                                            <option value={package.clone()} selected={package == selected}>{package}</option>
+                                        })}
+                                    </select>
+                                </div>
+                            </div>
+                        }
+                    </div>
+                </div>
+                <div class="panel-block item-grid">{items}</div>
+            </div>
+        </div>
+    }
+}
@@ -1,6 +1,9 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use barohead_db::ModFilter;
+
+use crate::mod_filter::ModFilterContext;
 use crate::routes::Route;
 
 use crate::components::ItemSearch;
@@ -13,6 +16,14 @@ pub fn nav() -> Html {
         Callback::from(move |_| is_active.set(!*is_active))
     };
 
+    // Lists leave items out while a mod filter is on, so say so everywhere.
+    let mod_filter = use_context::<ModFilterContext>().unwrap();
+    let filtering = match &*mod_filter {
+        ModFilter::All => None,
+        ModFilter::Only(package) => Some(format!("Only {}", package)),
+        ModFilter::Hide(package) => Some(format!("Hiding {}", package)),
+    };
+
     let aria_expanded = if *is_active { "true" } else { "false" };
     html! {
         <nav class="navbar is-fixed-top" role="navigation" aria-label="main navigation">
@@ -37,8 +48,36 @@ pub fn nav() -> Html {
                     <div class="navbar-item">
                         <ItemSearch />
                     </div>
+                    <Link<Route> to={Route::Combine} classes="navbar-item">
+                        {"Combine"}
+                    </Link<Route>>
+                    <Link<Route> to={Route::Plan} classes="navbar-item">
+                        {"Plan"}
+                    </Link<Route>>
+                    <div class="navbar-item has-dropdown is-hoverable">
+                        <a class="navbar-link">{"Reports"}</a>
+                        <div class="navbar-dropdown">
+                            <Link<Route> to={Route::Loops} classes="navbar-item">
+                                {"Duplication Loops"}
+                            </Link<Route>>
+                            <Link<Route> to={Route::Coverage} classes="navbar-item">
+                                {"Unobtainable and Unused Items"}
+                            </Link<Route>>
+                            <Link<Route> to={Route::Salvage} classes="navbar-item">
+                                {"Sell or Deconstruct"}
+                            </Link<Route>>
+                            <Link<Route> to={Route::Mods} classes="navbar-item">
+                                {"Mods"}
+                            </Link<Route>>
+                        </div>
+                    </div>
                 </div>
                 <div class="navbar-end">
+                    if let Some(filtering) = filtering {
+                        <Link<Route> to={Route::Mods} classes="navbar-item">
+                            <span class="tag is-info">{filtering}</span>
+                        </Link<Route>>
+                    }
                     <a class="navbar-item" href="https://github.com/thefloweringash/barohead">{"GitHub"}</a>
                 </div>
             </div>
@@ -0,0 +1,355 @@
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use barohead_data::items::StoreIdentifier;
+use barohead_db::{Material, StoreSummary, DB, INTERESTING_MERCHANTS};
+
+use crate::campaign::CampaignContext;
+use crate::components::{
+    pricing::format_price, CampaignSettings, ItemSearch, ItemThumbnail, ScheduleView,
+};
+use crate::plan::{Plan, PlanQuery};
+use crate::routes::Route;
+
+pub(crate) fn format_duration(seconds: f32) -> String {
+    let seconds = seconds.round() as i32;
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+fn material_html(material: &Material, amount: i32) -> Html {
+    match material {
+        Material::Item(item_ref) => {
+            html! { <ItemThumbnail item_ref={*item_ref} amount={amount} link=true /> }
+        }
+        Material::Tag(tag) => html! {
+            <Link<Route> to={Route::Tag { tag: tag.clone() }}>
+                {format!("{} x any {}", amount, tag)}
+            </Link<Route>>
+        },
+    }
+}
+
+#[function_component(PlanView)]
+pub fn plan_view() -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let campaign = use_context::<CampaignContext>().unwrap();
+    let location = use_location().unwrap();
+    let navigator = use_navigator().unwrap();
+    let plan = use_state(Plan::default);
+    // Whether the plan came from a shared link and differs from the saved
+    // one. It stays out of storage until the user says what to do with it.
+    let from_link = use_state(|| false);
+
+    {
+        let plan = plan.clone();
+        let from_link = from_link.clone();
+        let shared = location.query::<PlanQuery>().ok();
+        use_effect_with_deps(
+            move |shared| {
+                let saved = Plan::load();
+                let shared = shared.as_ref().map(Plan::from_query);
+                from_link.set(shared.is_some() && shared != saved);
+                plan.set(shared.or(saved).unwrap_or_default());
+                || ()
+            },
+            shared,
+        );
+    }
+
+    let update = {
+        let plan = plan.clone();
+        let from_link = *from_link;
+        move |change: Box<dyn Fn(&mut Plan)>| {
+            let mut next = (*plan).clone();
+            change(&mut next);
+            if !from_link {
+                next.save();
+            }
+            plan.set(next);
+        }
+    };
+
+    // Leaving the shared link reloads whatever is saved by then.
+    let on_keep = {
+        let plan = plan.clone();
+        let navigator = navigator.clone();
+        Callback::from(move |_| {
+            plan.save();
+            navigator.replace(&Route::Plan);
+        })
+    };
+    let on_merge = {
+        let plan = plan.clone();
+        let navigator = navigator.clone();
+        Callback::from(move |_| {
+            let mut saved = Plan::load().unwrap_or_default();
+            for (id, quantity) in &plan.targets {
+                saved.add(id, *quantity);
+            }
+            saved.save();
+            navigator.replace(&Route::Plan);
+        })
+    };
+    let on_discard = Callback::from(move |_| navigator.replace(&Route::Plan));
+
+    let on_add = {
+        let update = update.clone();
+        let db = db.clone();
+        Callback::from(move |item_ref| {
+            let id = db.get_item(item_ref).unwrap().id.clone();
+            update(Box::new(move |plan| plan.add(&id, 1)));
+        })
+    };
+    let on_store = {
+        let update = update.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target().unwrap().unchecked_into();
+            if let Some(store) = StoreIdentifier::from_internal_name(&select.value()) {
+                update(Box::new(move |plan| plan.store = store));
+            }
+        })
+    };
+    let on_clear = {
+        let update = update.clone();
+        Callback::from(move |_| update(Box::new(|plan| plan.targets.clear())))
+    };
+    let on_print = Callback::from(|_| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.print();
+        }
+    });
+
+    let target_rows = plan
+        .resolve(&db)
+        .into_iter()
+        .map(|(item_ref, quantity)| {
+            let id = db.get_item(item_ref).unwrap().id.clone();
+            let on_quantity = {
+                let update = update.clone();
+                let id = id.clone();
+                Callback::from(move |e: Event| {
+                    let input: HtmlInputElement = e.target().unwrap().unchecked_into();
+                    if let Ok(quantity) = input.value().parse::<i32>() {
+                        let id = id.clone();
+                        update(Box::new(move |plan| {
+                            for (target, existing) in plan.targets.iter_mut() {
+                                if *target == id {
+                                    *existing = quantity.max(1);
+                                }
+                            }
+                        }));
+                    }
+                })
+            };
+            let on_remove = {
+                let update = update.clone();
+                Callback::from(move |_| {
+                    let id = id.clone();
+                    update(Box::new(move |plan| {
+                        plan.targets.retain(|(target, _)| *target != id)
+                    }));
+                })
+            };
+            html! {
+                <tr>
+                    <td><ItemThumbnail {item_ref} link=true /></td>
+                    <td>
+                        <input class="input is-small no-print" type="number" min="1"
+                            value={quantity.to_string()} onchange={on_quantity} />
+                        <span class="print-only">{quantity}</span>
+                    </td>
+                    <td class="no-print"><button class="delete" onclick={on_remove}></button></td>
+                </tr>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let store_options = INTERESTING_MERCHANTS
+        .iter()
+        .map(|store| {
+            html! {
+                <option value={store.internal_name()} selected={*store == plan.store}>
+                    {db.store_translations.get_name(store).unwrap()}
+                </option>
+            }
+        })
+        .collect::<Html>();
+
+    let results = (!target_rows.is_empty()).then(|| {
+        let production = match db.production_plan(&plan.resolve(&db), plan.store, &campaign) {
+            Ok(production) => production,
+            Err(err) => {
+                return html! {
+                    <div class="notification is-danger">
+                        {format!("Unable to plan production: {}", err)}
+                    </div>
+                }
+            }
+        };
+
+        let purchases = production
+            .purchases
+            .iter()
+            .map(|purchase| {
+                let item = db.get_item(purchase.item_ref).unwrap();
+                let crafted_instead = item.fabricate.iter().any(|fabricate| !fabricate.recycle);
+                // Restocks are random, so warn once even a full shelf wouldn't do.
+                let short = item
+                    .price
+                    .as_ref()
+                    .and_then(|price| StoreSummary::for_store(price, plan.store).stock)
+                    .filter(|stock| purchase.amount > stock.max);
+                html! {
+                    <tr>
+                        <td><ItemThumbnail item_ref={purchase.item_ref} amount={purchase.amount} link=true /></td>
+                        <td>{format_price(purchase.unit_price)}</td>
+                        <td>{format_price(purchase.unit_price * purchase.amount)}</td>
+                        <td>
+                            {if crafted_instead { "Bought rather than made" } else { "" }}
+                            if let Some(stock) = short {
+                                <span class="tag is-warning">{format!("Stocks at most {}", stock.max)}</span>
+                            }
+                        </td>
+                    </tr>
+                }
+            })
+            .collect::<Html>();
+
+        let unavailable = production
+            .unavailable
+            .iter()
+            .map(|(material, amount)| html! { <div class="panel-block">{material_html(material, *amount)}</div> })
+            .collect::<Html>();
+
+        let steps = production
+            .bom
+            .steps
+            .iter()
+            .map(|step| {
+                let fabricate = db.get_fabricate(&step.fabricate_ref).unwrap();
+                let fabricators = fabricate
+                    .suitable_fabricators
+                    .iter()
+                    .map(|fabricator| fabricator.name())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                html! {
+                    <tr>
+                        <td><ItemThumbnail item_ref={step.fabricate_ref.item_ref} amount={step.needed} link=true /></td>
+                        <td>{step.runs}</td>
+                        <td>{fabricators}</td>
+                        <td>{format_duration(fabricate.time * step.runs as f32)}</td>
+                    </tr>
+                }
+            })
+            .collect::<Html>();
+
+        let fabricator_time = production
+            .fabricator_time
+            .iter()
+            .map(|(fabricator, time)| {
+                html! { <div class="panel-block">{format!("{}: {}", fabricator.name(), format_duration(*time))}</div> }
+            })
+            .collect::<Html>();
+
+        html! {
+            <>
+                <div class="panel">
+                    <div class="panel-heading">
+                        {"Shopping List: "}{format_price(production.cost())}
+                    </div>
+                    <div class="panel-block">
+                        <table class="table">
+                            <thead>
+                                <tr><th>{"Item"}</th><th>{"Each"}</th><th>{"Total"}</th><th></th></tr>
+                            </thead>
+                            <tbody>{purchases}</tbody>
+                        </table>
+                    </div>
+                </div>
+                if !production.unavailable.is_empty() {
+                    <div class="panel">
+                        <div class="panel-heading">{"Not Sold Here"}</div>
+                        {unavailable}
+                    </div>
+                }
+                <div class="panel">
+                    <div class="panel-heading">{"Fabricate"}</div>
+                    <div class="panel-block">
+                        <table class="table">
+                            <thead>
+                                <tr><th>{"Item"}</th><th>{"Runs"}</th><th>{"Where"}</th><th>{"Time"}</th></tr>
+                            </thead>
+                            <tbody>{steps}</tbody>
+                        </table>
+                    </div>
+                    {fabricator_time}
+                </div>
+                <ScheduleView bom={production.bom.clone()} />
+            </>
+        }
+    });
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{"Production Plan"}</h1>
+                <p class="no-print">
+                    {"Queue up what you need. Everything is bought from one store where that's cheaper than making it, and the plan is saved in this browser."}
+                </p>
+            </div>
+            if *from_link {
+                <div class="notification is-info is-light no-print">
+                    <p>{"This plan is from a shared link. It isn't saved, and changes to it won't be until you choose what to do with your own plan."}</p>
+                    <div class="buttons mt-3">
+                        <button class="button is-small" onclick={on_keep}>{"Replace my plan with it"}</button>
+                        <button class="button is-small" onclick={on_merge}>{"Add it to my plan"}</button>
+                        <button class="button is-small is-light" onclick={on_discard}>{"Back to my plan"}</button>
+                    </div>
+                </div>
+            }
+            <div class="box">
+                <div class="field is-grouped no-print">
+                    <div class="control">
+                        <ItemSearch onselect={on_add} />
+                    </div>
+                    <div class="control">
+                        <div class="select">
+                            <select onchange={on_store}>{store_options}</select>
+                        </div>
+                    </div>
+                    <div class="control">
+                        <Link<Route, PlanQuery> to={Route::Plan} query={Some(plan.to_query())} classes="button">
+                            {"Share link"}
+                        </Link<Route, PlanQuery>>
+                    </div>
+                    <div class="control">
+                        <button class="button" onclick={on_print}>{"Print"}</button>
+                    </div>
+                    <div class="control">
+                        <button class="button is-danger is-light" onclick={on_clear}>{"Clear"}</button>
+                    </div>
+                </div>
+                <CampaignSettings />
+                <p class="print-only">
+                    {"Shopping at "}{db.store_translations.get_name(&plan.store).unwrap()}
+                </p>
+                <table class="table">
+                    <thead>
+                        <tr><th>{"Item"}</th><th>{"Quantity"}</th><th class="no-print"></th></tr>
+                    </thead>
+                    <tbody>{target_rows}</tbody>
+                </table>
+            </div>
+            {results}
+        </div>
+    }
+}
@@ -1,101 +1,123 @@
 use std::rc::Rc;
 
-use barohead_data::items::{Price, StoreIdentifier};
 use yew::prelude::*;
+use yew_router::prelude::*;
 
-use crate::db::{ItemRef, DB, INTERESTING_MERCHANTS};
+use crate::campaign::CampaignContext;
+use crate::components::CampaignSettings;
+use crate::routes::Route;
+use barohead_db::{ItemRef, Stock, StoreSummary, DB, INTERESTING_MERCHANTS};
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
     pub item_ref: ItemRef,
+    /// What merchants pay assumes the item has this fraction of its
+    /// condition left.
+    #[prop_or(1.0)]
+    pub condition: f32,
 }
 
-struct StoreSummary {
-    pub sell: Option<i32>,
-    pub buy: i32,
+pub(crate) fn format_price(x: i32) -> Html {
+    html! {<> <strong>{x}</strong>{ " mk"} </>}
 }
 
-impl StoreSummary {
-    fn for_store(price: &Price, store: StoreIdentifier) -> Self {
-        let matching_modifier = price.modifiers.get(&store);
-
-        let sold = if Self::is_specialist_merchant(store) {
-            matching_modifier
-                .map(|m| m.sold.unwrap_or(price.sold))
-                .unwrap_or(false)
-        } else {
-            matching_modifier.and_then(|m| m.sold).unwrap_or(price.sold)
-        };
-
-        let multiplier = matching_modifier.and_then(|m| m.multiplier);
-
-        let sell_price: f32 = multiplier
-            .map(|mul| mul * price.baseprice as f32)
-            .unwrap_or(price.baseprice as f32);
-
-        let buy_price: f32 = sell_price * 0.3;
-
-        Self {
-            buy: buy_price as i32,
-            sell: sold.then_some(sell_price as i32),
-        }
-    }
+fn format_optional_price(x: Option<i32>) -> Html {
+    x.map(format_price).unwrap_or(html! { "N/A" })
+}
 
-    fn is_specialist_merchant(store: StoreIdentifier) -> bool {
-        matches!(
-            store,
-            StoreIdentifier::MerchantMedical
-                | StoreIdentifier::MerchantEngineering
-                | StoreIdentifier::MerchantArmory
-                | StoreIdentifier::MerchantClown
-                | StoreIdentifier::MerchantHusk
-        )
+pub(crate) fn format_stock(stock: Option<Stock>) -> String {
+    match stock {
+        Some(Stock { min, max }) if min == max => min.to_string(),
+        Some(Stock { min, max }) => format!("{}–{}", min, max),
+        None => String::new(),
     }
 }
 
-fn format_price(x: i32) -> Html {
-    html! {<> <strong>{x}</strong>{ " mk"} </>}
+pub(crate) fn format_difficulty(min_level_difficulty: i32) -> String {
+    if min_level_difficulty > 0 {
+        format!("{}%+", min_level_difficulty)
+    } else {
+        String::new()
+    }
 }
 
-fn format_optional_price(x: Option<i32>) -> Html {
-    x.map(format_price).unwrap_or(html! { "N/A" })
+/// Tags for the store's own rules about stocking the item, for stores that
+/// sell it.
+pub(crate) fn format_flags(pricing: &StoreSummary) -> Html {
+    if pricing.sell.is_none() {
+        return Html::default();
+    }
+    html! {
+        <div class="tags">
+            if pricing.requires_unlock {
+                <span class="tag is-warning">{"Once unlocked"}</span>
+            }
+            if !pricing.can_be_special {
+                <span class="tag">{"Never a special deal"}</span>
+            }
+        </div>
+    }
 }
 
 #[function_component(PricingView)]
-pub fn pricing_view(Props { item_ref }: &Props) -> Html {
+pub fn pricing_view(
+    Props {
+        item_ref,
+        condition,
+    }: &Props,
+) -> Html {
     let db = use_context::<Rc<DB>>().unwrap();
+    let campaign = use_context::<CampaignContext>().unwrap();
 
-    let item = db.get_item(*item_ref);
+    let item = db.get_item(*item_ref).unwrap();
 
     if let Some(price) = item.price.as_ref() {
         let rows = INTERESTING_MERCHANTS
             .iter()
             .map(|store| {
-                let store_name = db.store_translations.get_name(store);
-                let pricing = StoreSummary::for_store(price, *store);
+                let store_name = db.store_translations.get_name(store).unwrap();
+                let pricing = StoreSummary::for_condition(price, *store, &campaign, *condition);
+                let store_route = Route::Store {
+                    id: store.internal_name().to_owned(),
+                };
                 html! {
                     <tr>
-                        <th>{store_name}</th>
+                        <th><Link<Route> to={store_route}>{store_name}</Link<Route>></th>
                         <td>{format_optional_price(pricing.sell)}</td>
                         <td>{format_price(pricing.buy)}</td>
+                        <td>{format_stock(pricing.stock)}</td>
+                        <td>{format_difficulty(pricing.min_level_difficulty)}</td>
+                        <td>{format_flags(&pricing)}</td>
                     </tr>
                 }
             })
             .collect::<Vec<_>>();
 
         html! {
-            <table class="table">
-              <thead>
-                  <tr>
-                      <th>{"Merchant"}</th>
-                      <th>{"Buy"}</th>
-                      <th>{"Sell"}</th>
-                  </tr>
-              </thead>
-              <tbody>
-                  {rows}
-              </tbody>
-            </table>
+            <div>
+              <CampaignSettings />
+              <table class="table">
+                <thead>
+                    <tr>
+                        <th>{"Merchant"}</th>
+                        <th>{"Buy"}</th>
+                        if *condition < 1.0 {
+                            <th title="What the merchant pays for one in this condition">
+                                {format!("Sell at {}%", (condition * 100.0).round())}
+                            </th>
+                        } else {
+                            <th>{"Sell"}</th>
+                        }
+                        <th title="How many the merchant has after restocking">{"Stock"}</th>
+                        <th title="Only stocked at locations at least this difficult">{"Difficulty"}</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {rows}
+                </tbody>
+              </table>
+            </div>
         }
     } else {
         html! {
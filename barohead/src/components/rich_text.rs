@@ -0,0 +1,28 @@
+use yew::prelude::*;
+
+use barohead_data::rich_text::{Color, RichText};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub text: RichText,
+}
+
+#[function_component(ShowRichText)]
+pub fn show_rich_text(Props { text }: &Props) -> Html {
+    let spans = text
+        .spans
+        .iter()
+        .map(|span| match &span.color {
+            None => html! { <span>{&span.text}</span> },
+            // The stylesheet has the game's named colours, adjusted to be
+            // readable on a light page.
+            Some(Color::Named(name)) => {
+                html! { <span class={format!("rich-text-{}", name)}>{&span.text}</span> }
+            }
+            Some(Color::Rgb(r, g, b)) => html! {
+                <span style={format!("color: rgb({}, {}, {})", r, g, b)}>{&span.text}</span>
+            },
+        })
+        .collect::<Html>();
+    html! { <p class="rich-text">{spans}</p> }
+}
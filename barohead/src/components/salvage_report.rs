@@ -0,0 +1,144 @@
+use std::cmp::Reverse;
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use barohead_data::items::StoreIdentifier;
+use barohead_db::{ItemRef, SalvageValue, DB, INTERESTING_MERCHANTS};
+
+use crate::campaign::CampaignContext;
+use crate::components::sortable_table::{sort_rows, sortable_table, SortColumn};
+use crate::components::{
+    pricing::format_price, salvage_view::format_gain, CampaignSettings, ConditionSlider,
+    ItemThumbnail,
+};
+use crate::mod_filter::{self, ModFilterContext};
+
+type Row = (ItemRef, SalvageValue);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Column {
+    Name,
+    Sell,
+    Deconstruct,
+    Gain,
+}
+
+impl SortColumn<Row> for Column {
+    const ALL: &'static [Column] = &[
+        Column::Name,
+        Column::Sell,
+        Column::Deconstruct,
+        Column::Gain,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Column::Name => "Item",
+            Column::Sell => "Sold Whole",
+            Column::Deconstruct => "Deconstructed",
+            Column::Gain => "Better",
+        }
+    }
+
+    fn sort(self, rows: &mut [Row]) {
+        match self {
+            Column::Name => {}
+            Column::Sell => rows.sort_by_key(|(_, value)| Reverse(value.sell)),
+            Column::Deconstruct => rows.sort_by_key(|(_, value)| Reverse(value.deconstruct)),
+            Column::Gain => rows.sort_by_key(|(_, value)| Reverse(value.gain())),
+        }
+    }
+}
+
+/// Every item that can be both sold and deconstructed, and which of the two
+/// one merchant pays more for.
+#[function_component(SalvageReport)]
+pub fn salvage_report() -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let campaign = use_context::<CampaignContext>().unwrap();
+    let filter = use_context::<ModFilterContext>().unwrap();
+    let store = use_state(|| StoreIdentifier::MerchantOutpost);
+    let condition = use_state(|| 1.0);
+    // Biggest gain from deconstructing first.
+    let sort = use_state(|| (Column::Gain, false));
+
+    let on_store = {
+        let store = store.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target().unwrap().unchecked_into();
+            if let Some(selected) = StoreIdentifier::from_internal_name(&select.value()) {
+                store.set(selected);
+            }
+        })
+    };
+    let on_condition = {
+        let condition = condition.clone();
+        Callback::from(move |value| condition.set(value))
+    };
+
+    let mut rows = db
+        .salvageable_items()
+        .into_iter()
+        .filter(|item_ref| mod_filter::shows(&db, &filter, *item_ref))
+        .filter_map(|item_ref| {
+            let value = db
+                .salvage_value(item_ref, *store, &campaign, *condition)
+                .unwrap()?;
+            Some((item_ref, value))
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by_key(|(item_ref, _)| db.item_translations.get_name(item_ref).unwrap());
+    sort_rows(&mut rows, *sort);
+
+    let body = rows
+        .iter()
+        .map(|(item_ref, value)| {
+            html! {
+                <tr>
+                    <td><ItemThumbnail item_ref={*item_ref} link=true /></td>
+                    <td>{format_price(value.sell)}</td>
+                    <td>{format_price(value.deconstruct)}</td>
+                    <td>{format_gain(value.gain())}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    let store_options = INTERESTING_MERCHANTS
+        .iter()
+        .map(|merchant| {
+            html! {
+                <option value={merchant.internal_name()} selected={*merchant == *store}>
+                    {db.store_translations.get_name(merchant).unwrap()}
+                </option>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{"Sell or Deconstruct"}</h1>
+                <p>
+                    {"What a merchant pays for each item that can be both sold and deconstructed, against what they pay for everything deconstructing it gives. \
+                      Outputs are valued as new, and only count if an item that worn gives them."}
+                </p>
+            </div>
+            <div class="box">
+                <div class="field">
+                    <div class="control">
+                        <div class="select">
+                            <select onchange={on_store}>{store_options}</select>
+                        </div>
+                    </div>
+                </div>
+                <ConditionSlider condition={*condition} onchange={on_condition} />
+                <CampaignSettings />
+            </div>
+            {sortable_table(&sort, body)}
+        </div>
+    }
+}
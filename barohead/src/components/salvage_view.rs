@@ -0,0 +1,101 @@
+use std::rc::Rc;
+
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use barohead_db::{ItemRef, DB, INTERESTING_MERCHANTS};
+
+use crate::campaign::CampaignContext;
+use crate::components::pricing::format_price;
+use crate::routes::Route;
+
+pub(crate) fn format_gain(gain: i32) -> Html {
+    match gain {
+        0 => html! { <span>{"Either"}</span> },
+        gain if gain > 0 => {
+            html! { <span class="has-text-success">{format!("Deconstruct, +{} mk", gain)}</span> }
+        }
+        gain => html! { <span class="has-text-danger">{format!("Sell, +{} mk", -gain)}</span> },
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub item_ref: ItemRef,
+    /// The fraction of its condition the item has left.
+    #[prop_or(1.0)]
+    pub condition: f32,
+}
+
+/// Whether each merchant pays more for an item or for what deconstructing it
+/// gives. Empty for items that can't be both sold and deconstructed.
+#[function_component(SalvageView)]
+pub fn salvage_view(
+    Props {
+        item_ref,
+        condition,
+    }: &Props,
+) -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let campaign = use_context::<CampaignContext>().unwrap();
+
+    let mut unpriced_outputs = 0;
+    let rows = INTERESTING_MERCHANTS
+        .iter()
+        .filter_map(|store| {
+            let value = db
+                .salvage_value(*item_ref, *store, &campaign, *condition)
+                .unwrap()?;
+            unpriced_outputs = value.unpriced_outputs;
+            let store_route = Route::Store {
+                id: store.internal_name().to_owned(),
+            };
+            Some(html! {
+                <tr>
+                    <th>
+                        <Link<Route> to={store_route}>
+                            {db.store_translations.get_name(store).unwrap()}
+                        </Link<Route>>
+                    </th>
+                    <td>{format_price(value.sell)}</td>
+                    <td>{format_price(value.deconstruct)}</td>
+                    <td>{format_gain(value.gain())}</td>
+                </tr>
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if rows.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="panel">
+            <div class="panel-heading">{"Sell or Deconstruct"}</div>
+            <div class="panel-block">
+                <div>
+                    <p>
+                        {format!("What each merchant pays for one at {}% condition, against what they pay for everything deconstructing it gives. ", (condition * 100.0).round())}
+                        <Link<Route> to={Route::Salvage}>{"Compare every item"}</Link<Route>>
+                    </p>
+                    if unpriced_outputs > 0 {
+                        <p class="help">
+                            {format!("{} of the outputs can't be sold, so aren't counted.", unpriced_outputs)}
+                        </p>
+                    }
+                    <table class="table">
+                        <thead>
+                            <tr>
+                                <th>{"Merchant"}</th>
+                                <th>{"Sold Whole"}</th>
+                                <th>{"Deconstructed"}</th>
+                                <th>{"Better"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>{rows}</tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use barohead_data::items::{Fabricator, Skill};
+use barohead_db::{BillOfMaterials, DB};
+
+use crate::components::plan_view::format_duration;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub bom: BillOfMaterials,
+}
+
+fn number_input(label: &'static str, value: i32, onchange: Callback<i32>) -> Html {
+    let onchange = Callback::from(move |e: Event| {
+        let input: HtmlInputElement = e.target().unwrap().unchecked_into();
+        if let Ok(value) = input.value().parse::<i32>() {
+            onchange.emit(value.max(0));
+        }
+    });
+    html! {
+        <div class="control">
+            <label class="label is-small">{label}</label>
+            <input class="input is-small" type="number" min="0" value={value.to_string()} {onchange} />
+        </div>
+    }
+}
+
+#[function_component(ScheduleView)]
+pub fn schedule_view(Props { bom }: &Props) -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let machines = use_state(|| {
+        [
+            (Fabricator::Fabricator, 1),
+            (Fabricator::MedicalFabricator, 1),
+            (Fabricator::VendingMachine, 0),
+        ]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>()
+    });
+    // Roughly a crew member a few missions in.
+    let skills = use_state(|| {
+        Skill::ALL
+            .into_iter()
+            .map(|skill| (skill, 50))
+            .collect::<BTreeMap<_, _>>()
+    });
+
+    let machine_inputs = Fabricator::ALL
+        .into_iter()
+        .map(|fabricator| {
+            let machines = machines.clone();
+            let count = machines.get(&fabricator).copied().unwrap_or(0);
+            number_input(
+                fabricator.name(),
+                count as i32,
+                Callback::from(move |count: i32| {
+                    let mut next = (*machines).clone();
+                    next.insert(fabricator.clone(), count as usize);
+                    machines.set(next);
+                }),
+            )
+        })
+        .collect::<Html>();
+
+    let skill_inputs = Skill::ALL
+        .into_iter()
+        .map(|skill| {
+            let skills = skills.clone();
+            let level = skills.get(&skill).copied().unwrap_or(0);
+            number_input(
+                skill.name(),
+                level,
+                Callback::from(move |level| {
+                    let mut next = (*skills).clone();
+                    next.insert(skill.clone(), level);
+                    skills.set(next);
+                }),
+            )
+        })
+        .collect::<Html>();
+
+    let schedule = db.schedule(bom, &machines, &skills).unwrap();
+    let duration = schedule.duration().max(1.0);
+
+    let rows = machines
+        .iter()
+        .flat_map(|(fabricator, count)| (0..*count).map(move |machine| (fabricator, machine)))
+        .map(|(fabricator, machine)| {
+            let bars = schedule
+                .runs
+                .iter()
+                .filter(|run| run.fabricator == *fabricator && run.machine == machine)
+                .map(|run| {
+                    let item_ref = bom.steps[run.step].fabricate_ref.item_ref;
+                    let name = db.item_translations.get_name(item_ref).unwrap();
+                    let style = format!(
+                        "left: {}%; width: {}%",
+                        run.start / duration * 100.0,
+                        (run.end - run.start) / duration * 100.0
+                    );
+                    let title = format!(
+                        "{}: {} to {}",
+                        name,
+                        format_duration(run.start),
+                        format_duration(run.end)
+                    );
+                    html! { <div class="gantt-bar" {style} {title}>{name}</div> }
+                })
+                .collect::<Html>();
+            html! {
+                <div class="gantt-row">
+                    <div class="gantt-label">{format!("{} {}", fabricator.name(), machine + 1)}</div>
+                    <div class="gantt-track">{bars}</div>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    let unscheduled = schedule
+        .unscheduled
+        .iter()
+        .map(|step| {
+            let item_ref = bom.steps[*step].fabricate_ref.item_ref;
+            db.item_translations.get_name(item_ref).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    html! {
+        <div class="panel">
+            <div class="panel-heading">
+                {format!("Schedule: {}", format_duration(schedule.duration()))}
+            </div>
+            <div class="panel-block no-print">
+                <div>
+                    <p class="has-text-weight-semibold">{"Fabricators aboard"}</p>
+                    <div class="field is-grouped">{machine_inputs}</div>
+                    <p class="has-text-weight-semibold">{"Skills of whoever is fabricating"}</p>
+                    <div class="field is-grouped">{skill_inputs}</div>
+                </div>
+            </div>
+            if !unscheduled.is_empty() {
+                <div class="panel-block">
+                    <div class="notification is-warning is-light">
+                        {format!("Can't be made with these fabricators: {}", unscheduled.join(", "))}
+                    </div>
+                </div>
+            }
+            <div class="panel-block">
+                <div class="gantt">{rows}</div>
+            </div>
+        </div>
+    }
+}
@@ -3,17 +3,19 @@ use std::rc::Rc;
 use yew::prelude::*;
 
 use barohead_data::items::ItemRef;
+use barohead_db::{self as db, DeconstructRef, DB};
 
-use crate::{
-    components::ItemThumbnail,
-    db,
-    db::{DeconstructRef, DB},
-};
+use crate::components::ItemThumbnail;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
-    pub self_ref: db::ItemRef,
+    #[prop_or_default]
+    pub self_ref: Option<db::ItemRef>,
     pub deconstruct_ref: DeconstructRef,
+    /// Mark outputs that an item with this fraction of its condition left
+    /// wouldn't give.
+    #[prop_or_default]
+    pub condition: Option<f32>,
 }
 
 #[function_component(ShowDeconstruct)]
@@ -21,11 +23,12 @@ pub fn show_deconstruct(
     Props {
         self_ref,
         deconstruct_ref,
+        condition,
     }: &Props,
 ) -> Html {
     let db = use_context::<Rc<DB>>().unwrap();
-    let deconstruct = db.get_deconstruct(deconstruct_ref);
-    let showing_self = deconstruct_ref.item_ref == *self_ref;
+    let deconstruct = db.get_deconstruct(deconstruct_ref).unwrap();
+    let showing_self = Some(deconstruct_ref.item_ref) == *self_ref;
     let required_items = deconstruct
         .required_items
         .iter()
@@ -53,15 +56,25 @@ pub fn show_deconstruct(
             let item_ref = db
                 .new_item_ref(produced_item.id.as_str())
                 .expect("Deconstruct Produced item");
-            // TODO: The produced items are conditional based on input condition.
-            let is_self = item_ref == *self_ref;
-
-            html! {
+            let is_self = Some(item_ref) == *self_ref;
+            let thumbnail = html! {
                 <ItemThumbnail
                     {item_ref}
                     link={!is_self}
                     amount={produced_item.amount}
                 />
+            };
+
+            let Some(min) = produced_item.mincondition else {
+                return thumbnail;
+            };
+            let classes = classes!(condition
+                .is_some_and(|condition| !produced_item.is_produced_at(condition))
+                .then_some("not-produced"));
+            html! {
+                <div class={classes} title={format!("Needs at least {}% condition", (min * 100.0).round())}>
+                    {thumbnail}
+                </div>
             }
         })
         .collect::<Vec<_>>();
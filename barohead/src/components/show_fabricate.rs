@@ -1,18 +1,17 @@
 use std::rc::Rc;
 
 use yew::prelude::*;
+use yew_router::prelude::*;
 
 use barohead_data::items::ItemRef;
+use barohead_db::{self as db, FabricateRef, DB};
 
-use crate::{
-    components::ItemThumbnail,
-    db,
-    db::{FabricateRef, DB},
-};
+use crate::{components::ItemThumbnail, routes::Route};
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
-    pub self_ref: db::ItemRef,
+    #[prop_or_default]
+    pub self_ref: Option<db::ItemRef>,
     pub fabricate_ref: FabricateRef,
 }
 
@@ -24,7 +23,7 @@ pub fn show_fabricate(
     }: &Props,
 ) -> Html {
     let db = use_context::<Rc<DB>>().unwrap();
-    let fabricate = db.get_fabricate(fabricate_ref);
+    let fabricate = db.get_fabricate(fabricate_ref).unwrap();
     let required_items = fabricate
         .required_items
         .iter()
@@ -33,7 +32,7 @@ pub fn show_fabricate(
                 let input_item_ref = db
                     .new_item_ref(input_item_id)
                     .expect("Fabricate required item");
-                let is_self = &input_item_ref == self_ref;
+                let is_self = Some(input_item_ref) == *self_ref;
                 html! {
                     <ItemThumbnail
                         item_ref={input_item_ref}
@@ -50,7 +49,7 @@ pub fn show_fabricate(
                     if required_item.amount != 1 {
                         <span class="amount">{required_item.amount}</span>
                     }
-                    <span class="name">{tag}</span>
+                    <Link<Route> to={Route::Tag { tag: tag.clone() }} classes="name">{tag}</Link<Route>>
                     if required_item.condition.is_some() {
                         <span class="condition">{format!("{:#?}", required_item.condition)}</span>
                     }
@@ -60,7 +59,7 @@ pub fn show_fabricate(
         })
         .collect::<Vec<_>>();
 
-    let output_is_self = fabricate_ref.item_ref == *self_ref;
+    let output_is_self = Some(fabricate_ref.item_ref) == *self_ref;
     html! {
         <div class="panel-block fabricate">
             <div class="required-items">{required_items}</div>
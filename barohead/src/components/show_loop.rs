@@ -0,0 +1,100 @@
+use std::rc::Rc;
+
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use barohead_db::{Node, ProcessRef, DB};
+
+use crate::components::ItemThumbnail;
+use crate::routes::Route;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// Index into [`DB::duplication_loops`].
+    pub idx: usize,
+}
+
+pub(crate) fn format_amount(amount: f32) -> String {
+    if (amount - amount.round()).abs() < 0.01 {
+        format!("{}", amount.round())
+    } else {
+        format!("{:.2}", amount)
+    }
+}
+
+#[function_component(ShowLoop)]
+pub fn show_loop(Props { idx }: &Props) -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let found = &db.duplication_loops().unwrap()[*idx];
+
+    let steps = found
+        .steps
+        .iter()
+        .map(|step| {
+            let (verb, item_ref) = match &step.process {
+                ProcessRef::Fabricate(fabricate_ref) => ("Fabricate", fabricate_ref.item_ref),
+                ProcessRef::Deconstruct(deconstruct_ref) => {
+                    ("Deconstruct", deconstruct_ref.item_ref)
+                }
+            };
+            let id = db.get_item(item_ref).unwrap().id.clone();
+            let name = db.item_translations.get_name(item_ref).unwrap();
+            html! {
+                <>
+                    <ItemThumbnail item_ref={step.input} link=true />
+                    <div class="production-arrow">
+                        {verb}{" "}
+                        <Link<Route> to={Route::Item { id }}>{name}</Link<Route>>
+                        {format!(" ×{}", format_amount(step.runs))}
+                        {" ->"}
+                    </div>
+                </>
+            }
+        })
+        .collect::<Html>();
+
+    let net = found
+        .net
+        .iter()
+        .map(|(node, amount)| {
+            let amount = if *amount > 0.0 {
+                format!("+{}", format_amount(*amount))
+            } else {
+                format_amount(*amount)
+            };
+            let item = match node {
+                Node::Item(item_ref) => html! { <ItemThumbnail item_ref={*item_ref} link=true /> },
+                Node::Tag(tag) => html! {
+                    <Link<Route> to={Route::Tag { tag: tag.clone() }} classes="item-thumbnail">
+                        {format!("any {}", tag)}
+                    </Link<Route>>
+                },
+            };
+            html! {
+                <div class="loop-net">
+                    <span class="amount">{amount}</span>
+                    {item}
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    let (tag_class, tag_text) = if found.is_free() {
+        ("tag is-danger", "Free")
+    } else {
+        ("tag is-warning", "Costs other items")
+    };
+
+    html! {
+        <div class="panel-block duplication-loop">
+            <div>
+                <span class={tag_class}>{tag_text}</span>
+                <div class="loop-steps">
+                    {steps}
+                    <ItemThumbnail item_ref={found.item_ref()} link=true />
+                </div>
+                <div class="loop-nets">{"Net per trip: "}{net}</div>
+            </div>
+        </div>
+    }
+}
@@ -1,13 +1,13 @@
 use yew::prelude::*;
 
-use crate::{
-    components::{ShowDeconstruct, ShowFabricate},
-    db::{ItemRef, ProcessRef},
-};
+use barohead_db::{ItemRef, ProcessRef};
+
+use crate::components::{ShowDeconstruct, ShowFabricate};
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
-    pub self_ref: ItemRef,
+    #[prop_or_default]
+    pub self_ref: Option<ItemRef>,
     pub process_ref: ProcessRef,
 }
 
@@ -0,0 +1,64 @@
+use yew::prelude::*;
+
+/// A column of a table the user can sort by clicking its heading.
+pub(crate) trait SortColumn<Row>: Copy + PartialEq + 'static {
+    const ALL: &'static [Self];
+
+    fn title(self) -> &'static str;
+
+    /// Sort `rows` in the order the first click on this column gives. Rows
+    /// arrive in name order. Numbers read best biggest first, so columns of
+    /// them should sort descending.
+    fn sort(self, rows: &mut [Row]);
+}
+
+/// Which column is sorted on, and whether it has been clicked again to
+/// reverse it.
+pub(crate) type Sort<C> = (C, bool);
+
+pub(crate) fn sort_rows<Row, C: SortColumn<Row>>(rows: &mut [Row], (column, reversed): Sort<C>) {
+    column.sort(rows);
+    if reversed {
+        rows.reverse();
+    }
+}
+
+/// A table whose column headings sort it, with `body` already sorted by
+/// [`sort_rows`].
+pub(crate) fn sortable_table<Row, C: SortColumn<Row>>(
+    sort: &UseStateHandle<Sort<C>>,
+    body: Html,
+) -> Html {
+    let (column, reversed) = **sort;
+    let headers = C::ALL
+        .iter()
+        .map(|header| {
+            let header = *header;
+            let onclick = {
+                let sort = sort.clone();
+                Callback::from(move |_| {
+                    let (column, reversed) = *sort;
+                    sort.set((header, column == header && !reversed));
+                })
+            };
+            let arrow = match (header == column, reversed) {
+                (false, _) => "",
+                (true, false) => " ▲",
+                (true, true) => " ▼",
+            };
+            html! {
+                <th class="is-clickable" {onclick}>{header.title()}{arrow}</th>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <>
+            <p class="help">{"Click a column heading to sort by it, and again to reverse."}</p>
+            <table class="table is-fullwidth is-hoverable">
+                <thead><tr>{headers}</tr></thead>
+                <tbody>{body}</tbody>
+            </table>
+        </>
+    }
+}
@@ -0,0 +1,84 @@
+use std::rc::Rc;
+
+use barohead_data::items::StoreIdentifier;
+use barohead_db::{StoreSummary, DB};
+use yew::prelude::*;
+
+use crate::campaign::CampaignContext;
+use crate::components::{
+    pricing::{format_difficulty, format_flags, format_price, format_stock},
+    CampaignSettings, ItemThumbnail,
+};
+use crate::mod_filter::{self, ModFilterContext};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub store: StoreIdentifier,
+}
+
+#[function_component(StoreView)]
+pub fn store_view(Props { store }: &Props) -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let campaign = use_context::<CampaignContext>().unwrap();
+    let filter = use_context::<ModFilterContext>().unwrap();
+
+    let name = db.store_translations.get_name(store).unwrap();
+
+    let mut stock = db
+        .item_refs()
+        .filter(|item_ref| mod_filter::shows(&db, &filter, *item_ref))
+        .filter_map(|item_ref| {
+            let price = db.get_item(item_ref).unwrap().price.as_ref()?;
+            let pricing = StoreSummary::for_campaign(price, *store, &campaign);
+            pricing.sell.map(|sell| (item_ref, sell, pricing))
+        })
+        .collect::<Vec<_>>();
+    stock.sort_by_key(|(item_ref, _, _)| db.item_translations.get_name(item_ref).unwrap());
+
+    let rows = stock
+        .into_iter()
+        .map(|(item_ref, sell, pricing)| {
+            html! {
+                <tr>
+                    <td><ItemThumbnail {item_ref} link=true /></td>
+                    <td>{format_price(sell)}</td>
+                    <td>{format_price(pricing.buy)}</td>
+                    <td>{format_stock(pricing.stock)}</td>
+                    <td>{format_difficulty(pricing.min_level_difficulty)}</td>
+                    <td>{format_flags(&pricing)}</td>
+                </tr>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{name}</h1>
+            </div>
+            <div class="box">
+                <CampaignSettings />
+            </div>
+            <div class="panel">
+                <div class="panel-heading">{format!("Sold Here ({})", rows.len())}</div>
+                <div class="panel-block">
+                    <table class="table">
+                        <thead>
+                            <tr>
+                                <th>{"Item"}</th>
+                                <th>{"Buy"}</th>
+                                <th>{"Sell"}</th>
+                                <th title="How many the merchant has after restocking">{"Stock"}</th>
+                                <th title="Only stocked at locations at least this difficult">{"Difficulty"}</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {rows}
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+    }
+}
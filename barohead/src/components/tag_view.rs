@@ -0,0 +1,59 @@
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+use barohead_db::DB;
+
+use crate::components::{ItemThumbnail, ShowProcess};
+use crate::mod_filter::{self, ModFilterContext};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub tag: AttrValue,
+}
+
+#[function_component(TagView)]
+pub fn tag_view(Props { tag }: &Props) -> Html {
+    let db = use_context::<Rc<DB>>().unwrap();
+    let filter = use_context::<ModFilterContext>().unwrap();
+
+    let used_by = db
+        .get_tag_used_by(tag)
+        .map(|used_by| {
+            used_by
+                .iter()
+                .map(|process_ref| {
+                    let process_ref = process_ref.clone();
+                    html! {
+                        <ShowProcess {process_ref} />
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let mut tagged = db.items_with_tag(tag);
+    tagged.retain(|item_ref| mod_filter::shows(&db, &filter, *item_ref));
+    tagged.sort_by_key(|item_ref| db.item_translations.get_name(item_ref).unwrap());
+    let tagged = tagged
+        .into_iter()
+        .map(|item_ref| html! { <ItemThumbnail {item_ref} link=true /> })
+        .collect::<Vec<_>>();
+
+    html! {
+        <div class="container">
+            <div class="content">
+                <h1>{format!("Tag: {}", tag)}</h1>
+                <p>{"Recipes that accept any item with this tag, and the items that have it."}</p>
+            </div>
+            <div class="panel">
+                <div class="panel-heading">{format!("Items With This Tag ({})", tagged.len())}</div>
+                <div class="panel-block item-grid">{tagged}</div>
+            </div>
+            <div class="panel">
+                <div class="panel-heading">{format!("Used By ({})", used_by.len())}</div>
+                {used_by}
+            </div>
+        </div>
+    }
+}
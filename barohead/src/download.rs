@@ -0,0 +1,25 @@
+use js_sys::Array;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+/// Offer some generated text to the user as a file download.
+pub fn download(filename: &str, mime_type: &str, contents: &str) -> Result<(), JsValue> {
+    let mut options = BlobPropertyBag::new();
+    // The web-sys that goes with our wasm-bindgen only has the builder style
+    // setter, which newer versions deprecate.
+    #[allow(deprecated)]
+    options.type_(mime_type);
+    let parts = Array::of1(&JsValue::from_str(contents));
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+    let anchor: HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    Url::revoke_object_url(&url)
+}
@@ -0,0 +1,8 @@
+pub mod app;
+pub mod campaign;
+pub mod components;
+pub mod download;
+pub mod loader;
+pub mod mod_filter;
+pub mod plan;
+pub mod routes;
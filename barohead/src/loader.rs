@@ -0,0 +1,139 @@
+use std::fmt;
+
+use gloo_net::http::Request;
+use js_sys::{Array, Reflect, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Cache, ReadableStreamDefaultReader};
+
+// The database is served next to the wasm bundle. The hash is computed by
+// build.rs, so the url changes whenever the contents do, which lets us cache
+// it forever.
+pub const ITEMDB_URL: &str = concat!("recipes.bincode?v=", env!("ITEMDB_HASH"));
+pub const ICON_ATLAS_URL: &str = concat!("icons.png?v=", env!("ICON_ATLAS_HASH"));
+
+const CACHE_NAME: &str = "barohead-itemdb";
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Progress {
+    pub loaded: usize,
+    pub total: Option<usize>,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Network(gloo_net::Error),
+    Status(u16, String),
+    Js(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Network(err) => write!(f, "network error: {}", err),
+            LoadError::Status(status, text) => write!(f, "server responded {} {}", status, text),
+            LoadError::Js(err) => write!(f, "browser error: {}", err),
+        }
+    }
+}
+
+impl From<gloo_net::Error> for LoadError {
+    fn from(err: gloo_net::Error) -> Self {
+        LoadError::Network(err)
+    }
+}
+
+impl From<JsValue> for LoadError {
+    fn from(err: JsValue) -> Self {
+        LoadError::Js(format!("{:?}", err))
+    }
+}
+
+/// Fetch the raw item database, preferring a copy from the browser cache.
+pub async fn fetch_item_db(on_progress: impl Fn(Progress)) -> Result<Vec<u8>, LoadError> {
+    // The Cache API is only available in secure contexts, so treat it as
+    // optional.
+    let cache = open_cache().await.ok();
+
+    if let Some(cache) = &cache {
+        if let Some(bytes) = cached_item_db(cache).await? {
+            return Ok(bytes);
+        }
+    }
+
+    let bytes = download(&on_progress).await?;
+
+    if let Some(cache) = &cache {
+        // Failing to cache isn't fatal, we'll just download it again next time.
+        let _ = store_item_db(cache, &bytes).await;
+    }
+
+    Ok(bytes)
+}
+
+async fn open_cache() -> Result<Cache, LoadError> {
+    let caches = web_sys::window()
+        .ok_or_else(|| LoadError::Js("no window".to_owned()))?
+        .caches()?;
+    Ok(JsFuture::from(caches.open(CACHE_NAME)).await?.dyn_into()?)
+}
+
+async fn cached_item_db(cache: &Cache) -> Result<Option<Vec<u8>>, LoadError> {
+    let cached = JsFuture::from(cache.match_with_str(ITEMDB_URL)).await?;
+    if cached.is_undefined() {
+        return Ok(None);
+    }
+    let response: web_sys::Response = cached.dyn_into()?;
+    let buffer = JsFuture::from(response.array_buffer()?).await?;
+    Ok(Some(Uint8Array::new(&buffer).to_vec()))
+}
+
+async fn store_item_db(cache: &Cache, bytes: &[u8]) -> Result<(), LoadError> {
+    // Anything else in the cache is a previous version of the database.
+    let keys: Array = JsFuture::from(cache.keys()).await?.dyn_into()?;
+    for key in keys.iter() {
+        let request: web_sys::Request = key.dyn_into()?;
+        JsFuture::from(cache.delete_with_request(&request)).await?;
+    }
+
+    let mut body = bytes.to_vec();
+    let response = web_sys::Response::new_with_opt_u8_array(Some(&mut body))?;
+    JsFuture::from(cache.put_with_str(ITEMDB_URL, &response)).await?;
+    Ok(())
+}
+
+async fn download(on_progress: &impl Fn(Progress)) -> Result<Vec<u8>, LoadError> {
+    let response = Request::get(ITEMDB_URL).send().await?;
+    if !response.ok() {
+        return Err(LoadError::Status(response.status(), response.status_text()));
+    }
+
+    let total = response
+        .headers()
+        .get("content-length")
+        .and_then(|length| length.parse::<usize>().ok());
+
+    let body = match response.body() {
+        Some(body) => body,
+        None => return Ok(response.binary().await?),
+    };
+
+    let reader: ReadableStreamDefaultReader = body.get_reader().unchecked_into();
+    let mut bytes = Vec::with_capacity(total.unwrap_or(0));
+    on_progress(Progress { loaded: 0, total });
+
+    loop {
+        let chunk = JsFuture::from(reader.read()).await?;
+        if Reflect::get(&chunk, &"done".into())?.is_truthy() {
+            break;
+        }
+        let value: Uint8Array = Reflect::get(&chunk, &"value".into())?.dyn_into()?;
+        bytes.extend(value.to_vec());
+        on_progress(Progress {
+            loaded: bytes.len(),
+            total,
+        });
+    }
+
+    Ok(bytes)
+}
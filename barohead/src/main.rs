@@ -1,11 +1,16 @@
 use std::panic;
 
-mod app;
-mod components;
-mod db;
-mod routes;
-
 fn main() {
     panic::set_hook(Box::new(console_error_panic_hook::hook));
-    yew::Renderer::<crate::app::App>::new().render();
+
+    // Prerendered pages carry a static copy of their content for crawlers and
+    // the like, which the app replaces.
+    if let Some(prerendered) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id("prerendered"))
+    {
+        prerendered.remove();
+    }
+
+    yew::Renderer::<barohead::app::App>::new().render();
 }
@@ -0,0 +1,42 @@
+use yew::UseStateHandle;
+
+use barohead_db::{ItemRef, ModFilter, DB};
+
+const STORAGE_KEY: &str = "barohead.modfilter";
+
+/// Which mods' items to show, shared by every page and changed from the mods
+/// page.
+pub type ModFilterContext = UseStateHandle<ModFilter>;
+
+/// The filter saved by [`save`], or showing everything.
+pub fn load() -> ModFilter {
+    let saved = web_sys::window()
+        .and_then(|window| window.local_storage().ok()?)
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok()?);
+    saved
+        .and_then(|saved| {
+            let (mode, package) = saved.split_once(':')?;
+            match mode {
+                "only" => Some(ModFilter::Only(package.to_owned())),
+                "hide" => Some(ModFilter::Hide(package.to_owned())),
+                _ => None,
+            }
+        })
+        .unwrap_or_default()
+}
+
+pub fn save(filter: &ModFilter) {
+    if let Some(storage) = web_sys::window().and_then(|window| window.local_storage().ok()?) {
+        // As with the campaign, failing to save only means starting over.
+        let _ = match filter {
+            ModFilter::All => storage.remove_item(STORAGE_KEY),
+            ModFilter::Only(package) => storage.set_item(STORAGE_KEY, &format!("only:{}", package)),
+            ModFilter::Hide(package) => storage.set_item(STORAGE_KEY, &format!("hide:{}", package)),
+        };
+    }
+}
+
+/// Whether a list should show an item under the filter.
+pub fn shows(db: &DB, filter: &ModFilter, item_ref: ItemRef) -> bool {
+    filter.matches(db.provenance(item_ref).unwrap())
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+use barohead_data::items::StoreIdentifier;
+use barohead_db::{ItemRef, DB, INTERESTING_MERCHANTS};
+
+const STORAGE_KEY: &str = "barohead.plan";
+
+/// The items someone wants to end up with, and where they'll shop. Items are
+/// kept by id, so that a saved or shared plan survives a data update.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Plan {
+    pub targets: Vec<(String, i32)>,
+    pub store: StoreIdentifier,
+}
+
+impl Default for Plan {
+    fn default() -> Self {
+        Self {
+            targets: vec![],
+            store: INTERESTING_MERCHANTS[0],
+        }
+    }
+}
+
+/// A plan as it appears in a url, `?items=fuel:2,wire:10&store=merchantcity`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct PlanQuery {
+    pub items: String,
+    pub store: String,
+}
+
+impl Plan {
+    pub fn to_query(&self) -> PlanQuery {
+        PlanQuery {
+            items: self
+                .targets
+                .iter()
+                .map(|(id, quantity)| format!("{}:{}", id, quantity))
+                .collect::<Vec<_>>()
+                .join(","),
+            store: self.store.internal_name().to_owned(),
+        }
+    }
+
+    pub fn from_query(query: &PlanQuery) -> Self {
+        let targets = query
+            .items
+            .split(',')
+            .filter_map(|target| {
+                let (id, quantity) = target.split_once(':')?;
+                Some((id.to_owned(), quantity.parse().ok().filter(|q| *q > 0)?))
+            })
+            .collect();
+        Self {
+            targets,
+            store: StoreIdentifier::from_internal_name(&query.store)
+                .unwrap_or(INTERESTING_MERCHANTS[0]),
+        }
+    }
+
+    /// The plan saved by [`Plan::save`], if there is one.
+    pub fn load() -> Option<Self> {
+        let storage = web_sys::window()?.local_storage().ok()??;
+        let saved = storage.get_item(STORAGE_KEY).ok()??;
+        let (items, store) = saved.split_once('&')?;
+        Some(Self::from_query(&PlanQuery {
+            items: items.to_owned(),
+            store: store.to_owned(),
+        }))
+    }
+
+    pub fn save(&self) {
+        let query = self.to_query();
+        if let Some(storage) = web_sys::window().and_then(|window| window.local_storage().ok()?) {
+            // Private browsing can refuse to store anything, and there's
+            // nothing useful to do about it.
+            let _ = storage.set_item(STORAGE_KEY, &format!("{}&{}", query.items, query.store));
+        }
+    }
+
+    /// The targets that exist in this database.
+    pub fn resolve(&self, db: &DB) -> Vec<(ItemRef, i32)> {
+        self.targets
+            .iter()
+            .filter_map(|(id, quantity)| Some((db.new_item_ref(id).ok()?, *quantity)))
+            .collect()
+    }
+
+    /// Add to an item's quantity, appending it if it's new.
+    pub fn add(&mut self, id: &str, quantity: i32) {
+        match self.targets.iter_mut().find(|(target, _)| target == id) {
+            Some((_, existing)) => *existing += quantity,
+            None => self.targets.push((id.to_owned(), quantity)),
+        }
+    }
+}
@@ -6,4 +6,24 @@ pub enum Route {
     Home,
     #[at("/item/:id")]
     Item { id: String },
+    #[at("/item/:id/graph")]
+    ItemGraph { id: String },
+    #[at("/tag/:tag")]
+    Tag { tag: String },
+    #[at("/store/:id")]
+    Store { id: String },
+    #[at("/category/:name")]
+    Category { name: String },
+    #[at("/combine")]
+    Combine,
+    #[at("/plan")]
+    Plan,
+    #[at("/reports/loops")]
+    Loops,
+    #[at("/reports/coverage")]
+    Coverage,
+    #[at("/reports/salvage")]
+    Salvage,
+    #[at("/mods")]
+    Mods,
 }
@@ -0,0 +1,255 @@
+//! The on-disk form of the item database: a small header followed by the
+//! [`packed`](crate::packed) payload.
+//!
+//! Layout:
+//!
+//! ```text
+//! MAGIC | schema version (u32 le) | header length (u32 le) | Header (bincode) | payload
+//! ```
+//!
+//! The schema version is outside the bincode header so that it can still be
+//! read when the header itself changes shape.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::items::ItemDB;
+use crate::packed::{self, PackError};
+
+pub const MAGIC: &[u8; 8] = b"BAROHEAD";
+
+/// Bump this whenever anything in [`crate::items`] or [`crate::packed`]
+/// changes shape, and decide whether older files can be migrated.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Header {
+    pub schema_version: u32,
+    pub game_version: Option<String>,
+    /// Seconds since the unix epoch.
+    pub build_timestamp: u64,
+    /// FNV-1a of the payload.
+    pub content_hash: u64,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Truncated,
+    UnknownFormat,
+    UnsupportedSchema(u32),
+    HashMismatch { expected: u64, actual: u64 },
+    Header(bincode::Error),
+    Payload(PackError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Truncated => write!(f, "the file is truncated"),
+            LoadError::UnknownFormat => write!(f, "this is not a barohead item database"),
+            LoadError::UnsupportedSchema(version) => write!(
+                f,
+                "the item database has schema version {}, but this build only understands version {}",
+                version, SCHEMA_VERSION
+            ),
+            LoadError::HashMismatch { expected, actual } => write!(
+                f,
+                "the item database is corrupt (content hash {:016x}, expected {:016x})",
+                actual, expected
+            ),
+            LoadError::Header(err) => write!(f, "unreadable header: {}", err),
+            LoadError::Payload(err) => write!(f, "unreadable item data: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// The build time, honouring `SOURCE_DATE_EPOCH` for reproducible builds.
+pub fn build_timestamp() -> u64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0)
+        })
+}
+
+pub fn write(itemdb: &ItemDB, game_version: Option<String>) -> Result<Vec<u8>, PackError> {
+    let payload = packed::encode(itemdb)?;
+    let header = Header {
+        schema_version: SCHEMA_VERSION,
+        game_version,
+        build_timestamp: build_timestamp(),
+        content_hash: fnv1a(&payload),
+    };
+    Ok(assemble(&header, &payload)?)
+}
+
+fn assemble(header: &Header, payload: &[u8]) -> Result<Vec<u8>, bincode::Error> {
+    let header_bytes = bincode::serialize(header)?;
+
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 8 + header_bytes.len() + payload.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&header.schema_version.to_le_bytes());
+    bytes.extend_from_slice(&(header_bytes.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&header_bytes);
+    bytes.extend_from_slice(payload);
+    Ok(bytes)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, LoadError> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or(LoadError::Truncated)
+}
+
+/// Read only the header, without decoding the items.
+pub fn read_header(bytes: &[u8]) -> Result<Header, LoadError> {
+    if !bytes.starts_with(MAGIC) {
+        return Err(LoadError::UnknownFormat);
+    }
+
+    let schema_version = read_u32(bytes, MAGIC.len())?;
+    if schema_version != SCHEMA_VERSION {
+        return Err(LoadError::UnsupportedSchema(schema_version));
+    }
+
+    let header_start = MAGIC.len() + 8;
+    let header_bytes = bytes
+        .get(header_start..header_end(bytes)?)
+        .ok_or(LoadError::Truncated)?;
+    bincode::deserialize(header_bytes).map_err(LoadError::Header)
+}
+
+// Where the payload starts. The length is untrusted, and could overflow a
+// 32 bit usize.
+fn header_end(bytes: &[u8]) -> Result<usize, LoadError> {
+    let header_len = read_u32(bytes, MAGIC.len() + 4)? as usize;
+    (MAGIC.len() + 8)
+        .checked_add(header_len)
+        .ok_or(LoadError::Truncated)
+}
+
+pub fn read(bytes: &[u8]) -> Result<(Header, ItemDB), LoadError> {
+    if !bytes.starts_with(MAGIC) {
+        return migrate_headerless(bytes);
+    }
+
+    let header = read_header(bytes)?;
+    let payload = &bytes[header_end(bytes)?..];
+
+    let actual = fnv1a(payload);
+    if actual != header.content_hash {
+        return Err(LoadError::HashMismatch {
+            expected: header.content_hash,
+            actual,
+        });
+    }
+
+    let itemdb = packed::decode(payload).map_err(LoadError::Payload)?;
+    Ok((header, itemdb))
+}
+
+// Files from before the header existed are either bare packed data or plain
+// bincode from before that. We report them as schema version 0.
+fn migrate_headerless(bytes: &[u8]) -> Result<(Header, ItemDB), LoadError> {
+    let itemdb = packed::decode_headerless(bytes).ok_or(LoadError::UnknownFormat)?;
+    let header = Header {
+        schema_version: 0,
+        game_version: None,
+        build_timestamp: 0,
+        content_hash: fnv1a(bytes),
+    };
+    Ok((header, itemdb))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::packed::tests::sample;
+
+    #[test]
+    fn header_round_trips() {
+        let itemdb = sample();
+        let bytes = write(&itemdb, Some("1.2.3.4".to_owned())).unwrap();
+
+        let header = read_header(&bytes).unwrap();
+        assert_eq!(header.schema_version, SCHEMA_VERSION);
+        assert_eq!(header.game_version.as_deref(), Some("1.2.3.4"));
+
+        let (read_header, read_itemdb) = read(&bytes).unwrap();
+        assert_eq!(read_header, header);
+        assert_eq!(read_itemdb, itemdb);
+    }
+
+    #[test]
+    fn rejects_wrong_magic() {
+        let mut bytes = write(&sample(), None).unwrap();
+        bytes[0] = b'X';
+        assert!(matches!(read_header(&bytes), Err(LoadError::UnknownFormat)));
+        assert!(matches!(read(&bytes), Err(LoadError::UnknownFormat)));
+    }
+
+    #[test]
+    fn rejects_newer_schema() {
+        let payload = packed::encode(&sample()).unwrap();
+        let header = Header {
+            schema_version: SCHEMA_VERSION + 1,
+            game_version: None,
+            build_timestamp: 0,
+            content_hash: fnv1a(&payload),
+        };
+        let bytes = assemble(&header, &payload).unwrap();
+        assert!(matches!(
+            read_header(&bytes),
+            Err(LoadError::UnsupportedSchema(version)) if version == SCHEMA_VERSION + 1
+        ));
+        assert!(matches!(read(&bytes), Err(LoadError::UnsupportedSchema(_))));
+    }
+
+    #[test]
+    fn migrates_files_without_a_header() {
+        for bytes in [
+            &include_bytes!("../testdata/baseline.bincode")[..],
+            &include_bytes!("../testdata/packed-v1.bin")[..],
+        ] {
+            let (header, itemdb) = read(bytes).unwrap();
+            assert_eq!(header.schema_version, 0);
+            assert_eq!(itemdb.items.len(), 2);
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_header_length() {
+        let mut bytes = write(&sample(), None).unwrap();
+        bytes[MAGIC.len() + 4..MAGIC.len() + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(read_header(&bytes), Err(LoadError::Truncated)));
+        assert!(matches!(read(&bytes), Err(LoadError::Truncated)));
+    }
+
+    #[test]
+    fn rejects_corrupt_payload() {
+        let mut bytes = write(&sample(), None).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        assert!(matches!(read(&bytes), Err(LoadError::HashMismatch { .. })));
+        assert!(matches!(
+            read_header(&bytes[..MAGIC.len() + 6]),
+            Err(LoadError::Truncated)
+        ));
+    }
+}
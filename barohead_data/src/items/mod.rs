@@ -1,3 +1,6 @@
+// Changing anything in here changes the database file format, so remember to
+// bump `database::SCHEMA_VERSION`.
+
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
@@ -5,6 +8,20 @@ use std::collections::BTreeMap;
 pub struct ItemDB {
     pub texts: BTreeMap<Language, BTreeMap<String, String>>,
     pub items: Vec<Item>,
+    /// Content package names in load order. Empty if the extractor didn't
+    /// know about packages.
+    #[serde(default)]
+    pub packages: Vec<String>,
+    /// Set once pack-index has packed item icons into an atlas image.
+    #[serde(default)]
+    pub icon_atlas: Option<IconAtlas>,
+}
+
+/// The size of the icon atlas image, which is served next to the database.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IconAtlas {
+    pub width: u32,
+    pub height: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, Ord, Eq)]
@@ -12,13 +29,69 @@ pub enum Language {
     English,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct Item {
     pub id: String,
     pub nameidentifier: Option<String>,
+    /// The item this is a `variantof`. Everything below already includes
+    /// what was inherited from it.
+    #[serde(default)]
+    pub variant_of: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<Category>,
+    /// Where the game draws the item's icon from, as found by the extractor.
+    /// pack-index drops it, since the path is local to the extracting machine.
+    #[serde(default)]
+    pub icon: Option<Icon>,
+    /// Where the icon ended up in the icon atlas.
+    #[serde(default)]
+    pub atlas_rect: Option<Rect>,
     pub fabricate: Vec<Fabricate>,
     pub deconstruct: Vec<Deconstruct>,
     pub price: Option<Price>,
+    #[serde(default)]
+    pub provenance: Option<Provenance>,
+}
+
+/// Which content packages an item came from. An `<Override>` replaces the
+/// whole definition, but a variant copies whatever it leaves out, recipes and
+/// price included, from its base, which may come from another package.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Provenance {
+    /// The package whose definition won.
+    pub package: String,
+    /// Packages whose earlier definitions it replaced, oldest first.
+    pub overrides: Vec<String>,
+    /// For a variant, the packages whose definitions of its bases it was
+    /// built from, nearest base first, leaving out `package`.
+    #[serde(default)]
+    pub inherits: Vec<String>,
+}
+
+impl Provenance {
+    /// Every package that had a say in the item.
+    pub fn packages(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.package).chain(&self.inherits)
+    }
+}
+
+/// An item's `<InventoryIcon>`, or its `<Sprite>` if it has no icon.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Icon {
+    /// Path to the texture image on the machine that extracted it.
+    pub texture: String,
+    /// The part of the texture to use, or all of it if `None`.
+    pub source_rect: Option<Rect>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
 }
 
 impl Item {
@@ -26,6 +99,99 @@ impl Item {
         let id = self.nameidentifier.as_ref().unwrap_or(&self.id);
         format!("entityname.{}", id)
     }
+
+    pub fn description_text_key(&self) -> String {
+        let id = self.nameidentifier.as_ref().unwrap_or(&self.id);
+        format!("entitydescription.{}", id)
+    }
+}
+
+/// The game's item categories, used to group items in the editor and in
+/// store menus. An item can be in several.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd, Ord, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Structure,
+    Decorative,
+    Machine,
+    Medical,
+    Weapon,
+    Diving,
+    Equipment,
+    Fuel,
+    Electrical,
+    Material,
+    Alien,
+    Wrecked,
+    ItemAssembly,
+    Legacy,
+    Misc,
+}
+
+impl Category {
+    pub const ALL: [Category; 15] = [
+        Category::Structure,
+        Category::Decorative,
+        Category::Machine,
+        Category::Medical,
+        Category::Weapon,
+        Category::Diving,
+        Category::Equipment,
+        Category::Fuel,
+        Category::Electrical,
+        Category::Material,
+        Category::Alien,
+        Category::Wrecked,
+        Category::ItemAssembly,
+        Category::Legacy,
+        Category::Misc,
+    ];
+
+    pub fn from_internal_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.internal_name() == name)
+    }
+
+    pub fn internal_name(self) -> &'static str {
+        match self {
+            Category::Structure => "structure",
+            Category::Decorative => "decorative",
+            Category::Machine => "machine",
+            Category::Medical => "medical",
+            Category::Weapon => "weapon",
+            Category::Diving => "diving",
+            Category::Equipment => "equipment",
+            Category::Fuel => "fuel",
+            Category::Electrical => "electrical",
+            Category::Material => "material",
+            Category::Alien => "alien",
+            Category::Wrecked => "wrecked",
+            Category::ItemAssembly => "itemassembly",
+            Category::Legacy => "legacy",
+            Category::Misc => "misc",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Structure => "Structure",
+            Category::Decorative => "Decorative",
+            Category::Machine => "Machine",
+            Category::Medical => "Medical",
+            Category::Weapon => "Weapon",
+            Category::Diving => "Diving",
+            Category::Equipment => "Equipment",
+            Category::Fuel => "Fuel",
+            Category::Electrical => "Electrical",
+            Category::Material => "Material",
+            Category::Alien => "Alien",
+            Category::Wrecked => "Wrecked",
+            Category::ItemAssembly => "Item Assembly",
+            Category::Legacy => "Legacy",
+            Category::Misc => "Miscellaneous",
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -37,6 +203,8 @@ pub struct Fabricate {
     pub requires_recipe: bool,
     pub out_condition: f32,
     pub amount: i32,
+    /// A `recycleitem` recipe. It still turns `required_items` into this
+    /// item like any other, but from things that were made out of it.
     pub recycle: bool,
 }
 
@@ -62,6 +230,14 @@ pub struct ProducedItem {
     pub mincondition: Option<f32>,
 }
 
+impl ProducedItem {
+    /// Whether deconstructing an item with this much of its condition left,
+    /// as a fraction, gives this output.
+    pub fn is_produced_at(&self, condition: f32) -> bool {
+        self.mincondition.map_or(true, |min| condition >= min)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ConditionRange {
     pub min: Option<f32>,
@@ -76,6 +252,22 @@ pub enum Fabricator {
     VendingMachine,
 }
 
+impl Fabricator {
+    pub const ALL: [Fabricator; 3] = [
+        Fabricator::Fabricator,
+        Fabricator::MedicalFabricator,
+        Fabricator::VendingMachine,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fabricator::Fabricator => "Fabricator",
+            Fabricator::MedicalFabricator => "Medical Fabricator",
+            Fabricator::VendingMachine => "Vending Machine",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, Ord, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Skill {
@@ -87,6 +279,28 @@ pub enum Skill {
     Helm,
 }
 
+impl Skill {
+    pub const ALL: [Skill; 6] = [
+        Skill::Engineering,
+        Skill::Electrical,
+        Skill::Medical,
+        Skill::Mechanical,
+        Skill::Weapons,
+        Skill::Helm,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Skill::Engineering => "Engineering",
+            Skill::Electrical => "Electrical",
+            Skill::Medical => "Medical",
+            Skill::Mechanical => "Mechanical",
+            Skill::Weapons => "Weapons",
+            Skill::Helm => "Helm",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ItemRef {
@@ -111,6 +325,26 @@ pub enum StoreIdentifier {
 }
 
 impl StoreIdentifier {
+    pub const ALL: [StoreIdentifier; 11] = [
+        StoreIdentifier::MerchantOutpost,
+        StoreIdentifier::MerchantCity,
+        StoreIdentifier::MerchantResearch,
+        StoreIdentifier::MerchantMilitary,
+        StoreIdentifier::MerchantMine,
+        StoreIdentifier::MerchantMedical,
+        StoreIdentifier::MerchantEngineering,
+        StoreIdentifier::MerchantArmory,
+        StoreIdentifier::MerchantClown,
+        StoreIdentifier::MerchantHusk,
+        StoreIdentifier::MerchantTutorial,
+    ];
+
+    pub fn from_internal_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|store| store.internal_name() == name)
+    }
+
     pub fn internal_name(self) -> &'static str {
         match self {
             StoreIdentifier::MerchantOutpost => "merchantoutpost",
@@ -132,10 +366,21 @@ impl StoreIdentifier {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A store's own `<Price>`. Anything left out falls back to the item's.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct PriceModifier {
     pub multiplier: Option<f32>,
     pub sold: Option<bool>,
+    #[serde(default)]
+    pub min_available: Option<i32>,
+    #[serde(default)]
+    pub max_available: Option<i32>,
+    #[serde(default)]
+    pub min_level_difficulty: Option<i32>,
+    #[serde(default)]
+    pub requires_unlock: Option<bool>,
+    #[serde(default)]
+    pub can_be_special: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -143,4 +388,39 @@ pub struct Price {
     pub baseprice: i32,
     pub sold: bool,
     pub modifiers: BTreeMap<StoreIdentifier, PriceModifier>,
+    /// How many a store stocks, when it doesn't say itself. A store restocks
+    /// with a random amount in this range.
+    #[serde(default)]
+    pub min_available: Option<i32>,
+    #[serde(default)]
+    pub max_available: Option<i32>,
+    /// Only stocked at locations at least this difficult.
+    #[serde(default)]
+    pub min_level_difficulty: Option<i32>,
+    /// Only stocked once the item has been unlocked, usually by an event.
+    #[serde(default)]
+    pub requires_unlock: bool,
+    /// Whether it can turn up as a store's daily special or in demand.
+    #[serde(default = "can_be_special_default")]
+    pub can_be_special: bool,
+}
+
+fn can_be_special_default() -> bool {
+    true
+}
+
+/// What the game assumes for anything a `<Price>` leaves out.
+impl Default for Price {
+    fn default() -> Self {
+        Price {
+            baseprice: 0,
+            sold: true,
+            modifiers: BTreeMap::new(),
+            min_available: None,
+            max_available: None,
+            min_level_difficulty: None,
+            requires_unlock: false,
+            can_be_special: can_be_special_default(),
+        }
+    }
 }
@@ -1 +1,4 @@
+pub mod database;
 pub mod items;
+pub mod packed;
+pub mod rich_text;
@@ -0,0 +1,576 @@
+//! A compact encoding of [`ItemDB`] for shipping to the browser.
+//!
+//! Item identifiers are repeated all over the database, in every recipe and
+//! every text key. The packed form stores each distinct string once in a table
+//! and refers to it by index, and the whole thing is deflated on top of that.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use serde::{Deserialize, Serialize};
+
+use crate::items::*;
+
+mod legacy;
+
+type StringIndex = u32;
+
+#[derive(Debug)]
+pub enum PackError {
+    Io(io::Error),
+    Bincode(bincode::Error),
+    BadStringIndex(StringIndex),
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::Io(err) => write!(f, "{}", err),
+            PackError::Bincode(err) => write!(f, "{}", err),
+            PackError::BadStringIndex(idx) => write!(f, "string index {} out of range", idx),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+impl From<io::Error> for PackError {
+    fn from(err: io::Error) -> Self {
+        PackError::Io(err)
+    }
+}
+
+impl From<bincode::Error> for PackError {
+    fn from(err: bincode::Error) -> Self {
+        PackError::Bincode(err)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PackedItemDB {
+    strings: Vec<String>,
+    texts: BTreeMap<Language, Vec<(StringIndex, StringIndex)>>,
+    items: Vec<PackedItem>,
+    packages: Vec<StringIndex>,
+    icon_atlas: Option<IconAtlas>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct PackedItem {
+    id: StringIndex,
+    nameidentifier: Option<StringIndex>,
+    variant_of: Option<StringIndex>,
+    tags: Vec<StringIndex>,
+    categories: Vec<Category>,
+    icon: Option<PackedIcon>,
+    atlas_rect: Option<Rect>,
+    fabricate: Vec<PackedFabricate>,
+    deconstruct: Vec<PackedDeconstruct>,
+    price: Option<Price>,
+    provenance: Option<PackedProvenance>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct PackedProvenance {
+    package: StringIndex,
+    overrides: Vec<StringIndex>,
+    inherits: Vec<StringIndex>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct PackedIcon {
+    texture: StringIndex,
+    source_rect: Option<Rect>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct PackedFabricate {
+    suitable_fabricators: Vec<Fabricator>,
+    time: f32,
+    required_items: Vec<PackedRequiredItem>,
+    required_skills: BTreeMap<Skill, i32>,
+    requires_recipe: bool,
+    out_condition: f32,
+    amount: i32,
+    recycle: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct PackedDeconstruct {
+    time: f32,
+    required_items: Vec<PackedRequiredItem>,
+    required_skills: BTreeMap<Skill, i32>,
+    items: Vec<PackedProducedItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct PackedRequiredItem {
+    item: PackedItemRef,
+    amount: i32,
+    condition: Option<ConditionRange>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct PackedProducedItem {
+    id: StringIndex,
+    amount: i32,
+    mincondition: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum PackedItemRef {
+    Tag(StringIndex),
+    Id(StringIndex),
+}
+
+#[derive(Default)]
+struct StringTableBuilder {
+    strings: Vec<String>,
+    indexes: BTreeMap<String, StringIndex>,
+}
+
+impl StringTableBuilder {
+    fn intern(&mut self, s: &str) -> StringIndex {
+        if let Some(idx) = self.indexes.get(s) {
+            return *idx;
+        }
+        let idx = self.strings.len() as StringIndex;
+        self.strings.push(s.to_owned());
+        self.indexes.insert(s.to_owned(), idx);
+        idx
+    }
+
+    fn pack_required_items(&mut self, required_items: &[RequiredItem]) -> Vec<PackedRequiredItem> {
+        required_items
+            .iter()
+            .map(|required_item| PackedRequiredItem {
+                item: match &required_item.item {
+                    ItemRef::Tag(tag) => PackedItemRef::Tag(self.intern(tag)),
+                    ItemRef::Id(id) => PackedItemRef::Id(self.intern(id)),
+                },
+                amount: required_item.amount,
+                condition: required_item.condition.clone(),
+            })
+            .collect()
+    }
+
+    fn pack_item(&mut self, item: &Item) -> PackedItem {
+        PackedItem {
+            id: self.intern(&item.id),
+            nameidentifier: item.nameidentifier.as_deref().map(|s| self.intern(s)),
+            variant_of: item.variant_of.as_deref().map(|s| self.intern(s)),
+            tags: item.tags.iter().map(|tag| self.intern(tag)).collect(),
+            categories: item.categories.clone(),
+            icon: item.icon.as_ref().map(|icon| PackedIcon {
+                texture: self.intern(&icon.texture),
+                source_rect: icon.source_rect,
+            }),
+            atlas_rect: item.atlas_rect,
+            fabricate: item
+                .fabricate
+                .iter()
+                .map(|fabricate| PackedFabricate {
+                    suitable_fabricators: fabricate.suitable_fabricators.clone(),
+                    time: fabricate.time,
+                    required_items: self.pack_required_items(&fabricate.required_items),
+                    required_skills: fabricate.required_skills.clone(),
+                    requires_recipe: fabricate.requires_recipe,
+                    out_condition: fabricate.out_condition,
+                    amount: fabricate.amount,
+                    recycle: fabricate.recycle,
+                })
+                .collect(),
+            deconstruct: item
+                .deconstruct
+                .iter()
+                .map(|deconstruct| PackedDeconstruct {
+                    time: deconstruct.time,
+                    required_items: self.pack_required_items(&deconstruct.required_items),
+                    required_skills: deconstruct.required_skills.clone(),
+                    items: deconstruct
+                        .items
+                        .iter()
+                        .map(|produced_item| PackedProducedItem {
+                            id: self.intern(&produced_item.id),
+                            amount: produced_item.amount,
+                            mincondition: produced_item.mincondition,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            price: item.price.clone(),
+            provenance: item.provenance.as_ref().map(|provenance| PackedProvenance {
+                package: self.intern(&provenance.package),
+                overrides: provenance
+                    .overrides
+                    .iter()
+                    .map(|package| self.intern(package))
+                    .collect(),
+                inherits: provenance
+                    .inherits
+                    .iter()
+                    .map(|package| self.intern(package))
+                    .collect(),
+            }),
+        }
+    }
+}
+
+struct StringTable<'a> {
+    strings: &'a [String],
+}
+
+impl<'a> StringTable<'a> {
+    fn get(&self, idx: StringIndex) -> Result<String, PackError> {
+        self.strings
+            .get(idx as usize)
+            .cloned()
+            .ok_or(PackError::BadStringIndex(idx))
+    }
+
+    fn unpack_required_items(
+        &self,
+        required_items: &[PackedRequiredItem],
+    ) -> Result<Vec<RequiredItem>, PackError> {
+        required_items
+            .iter()
+            .map(|required_item| {
+                Ok(RequiredItem {
+                    item: match required_item.item {
+                        PackedItemRef::Tag(tag) => ItemRef::Tag(self.get(tag)?),
+                        PackedItemRef::Id(id) => ItemRef::Id(self.get(id)?),
+                    },
+                    amount: required_item.amount,
+                    condition: required_item.condition.clone(),
+                })
+            })
+            .collect()
+    }
+
+    fn unpack_item(&self, item: &PackedItem) -> Result<Item, PackError> {
+        Ok(Item {
+            id: self.get(item.id)?,
+            nameidentifier: item.nameidentifier.map(|idx| self.get(idx)).transpose()?,
+            variant_of: item.variant_of.map(|idx| self.get(idx)).transpose()?,
+            tags: item
+                .tags
+                .iter()
+                .map(|idx| self.get(*idx))
+                .collect::<Result<_, _>>()?,
+            categories: item.categories.clone(),
+            icon: item
+                .icon
+                .as_ref()
+                .map(|icon| -> Result<_, PackError> {
+                    Ok(Icon {
+                        texture: self.get(icon.texture)?,
+                        source_rect: icon.source_rect,
+                    })
+                })
+                .transpose()?,
+            atlas_rect: item.atlas_rect,
+            fabricate: item
+                .fabricate
+                .iter()
+                .map(|fabricate| {
+                    Ok(Fabricate {
+                        suitable_fabricators: fabricate.suitable_fabricators.clone(),
+                        time: fabricate.time,
+                        required_items: self.unpack_required_items(&fabricate.required_items)?,
+                        required_skills: fabricate.required_skills.clone(),
+                        requires_recipe: fabricate.requires_recipe,
+                        out_condition: fabricate.out_condition,
+                        amount: fabricate.amount,
+                        recycle: fabricate.recycle,
+                    })
+                })
+                .collect::<Result<_, PackError>>()?,
+            deconstruct: item
+                .deconstruct
+                .iter()
+                .map(|deconstruct| {
+                    Ok(Deconstruct {
+                        time: deconstruct.time,
+                        required_items: self.unpack_required_items(&deconstruct.required_items)?,
+                        required_skills: deconstruct.required_skills.clone(),
+                        items: deconstruct
+                            .items
+                            .iter()
+                            .map(|produced_item| {
+                                Ok(ProducedItem {
+                                    id: self.get(produced_item.id)?,
+                                    amount: produced_item.amount,
+                                    mincondition: produced_item.mincondition,
+                                })
+                            })
+                            .collect::<Result<_, PackError>>()?,
+                    })
+                })
+                .collect::<Result<_, PackError>>()?,
+            price: item.price.clone(),
+            provenance: item
+                .provenance
+                .as_ref()
+                .map(|provenance| -> Result<_, PackError> {
+                    Ok(Provenance {
+                        package: self.get(provenance.package)?,
+                        overrides: provenance
+                            .overrides
+                            .iter()
+                            .map(|idx| self.get(*idx))
+                            .collect::<Result<_, PackError>>()?,
+                        inherits: provenance
+                            .inherits
+                            .iter()
+                            .map(|idx| self.get(*idx))
+                            .collect::<Result<_, PackError>>()?,
+                    })
+                })
+                .transpose()?,
+        })
+    }
+}
+
+impl PackedItemDB {
+    pub fn pack(itemdb: &ItemDB) -> Self {
+        let mut builder = StringTableBuilder::default();
+
+        let items = itemdb
+            .items
+            .iter()
+            .map(|item| builder.pack_item(item))
+            .collect();
+
+        let texts = itemdb
+            .texts
+            .iter()
+            .map(|(language, texts)| {
+                let texts = texts
+                    .iter()
+                    .map(|(key, value)| (builder.intern(key), builder.intern(value)))
+                    .collect();
+                (language.clone(), texts)
+            })
+            .collect();
+
+        let packages = itemdb
+            .packages
+            .iter()
+            .map(|package| builder.intern(package))
+            .collect();
+
+        Self {
+            strings: builder.strings,
+            texts,
+            items,
+            packages,
+            icon_atlas: itemdb.icon_atlas.clone(),
+        }
+    }
+
+    pub fn unpack(&self) -> Result<ItemDB, PackError> {
+        let strings = StringTable {
+            strings: &self.strings,
+        };
+
+        let items = self
+            .items
+            .iter()
+            .map(|item| strings.unpack_item(item))
+            .collect::<Result<_, _>>()?;
+
+        let texts = self
+            .texts
+            .iter()
+            .map(|(language, texts)| {
+                let texts = texts
+                    .iter()
+                    .map(|(key, value)| Ok((strings.get(*key)?, strings.get(*value)?)))
+                    .collect::<Result<_, PackError>>()?;
+                Ok((language.clone(), texts))
+            })
+            .collect::<Result<_, PackError>>()?;
+
+        let packages = self
+            .packages
+            .iter()
+            .map(|idx| strings.get(*idx))
+            .collect::<Result<_, _>>()?;
+
+        Ok(ItemDB {
+            texts,
+            items,
+            packages,
+            icon_atlas: self.icon_atlas.clone(),
+        })
+    }
+}
+
+/// Pack and compress an [`ItemDB`].
+pub fn encode(itemdb: &ItemDB) -> Result<Vec<u8>, PackError> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    bincode::serialize_into(&mut encoder, &PackedItemDB::pack(itemdb))?;
+    encoder.flush()?;
+    Ok(encoder.finish()?)
+}
+
+fn inflate(bytes: &[u8]) -> Result<Vec<u8>, PackError> {
+    let mut decoder = DeflateDecoder::new(bytes);
+    let mut inflated = Vec::new();
+    decoder.read_to_end(&mut inflated)?;
+    Ok(inflated)
+}
+
+/// The inverse of [`encode`].
+pub fn decode(bytes: &[u8]) -> Result<ItemDB, PackError> {
+    let packed: PackedItemDB = bincode::deserialize(&inflate(bytes)?)?;
+    packed.unpack()
+}
+
+/// Read a database from before there was a header, or `None` if it isn't
+/// one.
+pub fn decode_headerless(bytes: &[u8]) -> Option<ItemDB> {
+    legacy::decode(bytes)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A database that uses every field, so nothing can go missing unnoticed
+    /// when packing.
+    pub(crate) fn sample() -> ItemDB {
+        let copper = Item {
+            id: "copper".to_owned(),
+            tags: vec!["smallitem".to_owned()],
+            categories: vec![Category::Material],
+            price: Some(Price {
+                baseprice: 40,
+                modifiers: [(
+                    StoreIdentifier::MerchantMine,
+                    PriceModifier {
+                        multiplier: Some(0.8),
+                        sold: Some(false),
+                        min_available: Some(2),
+                        max_available: Some(6),
+                        min_level_difficulty: Some(20),
+                        requires_unlock: Some(false),
+                        can_be_special: Some(true),
+                    },
+                )]
+                .into_iter()
+                .collect(),
+                min_available: Some(1),
+                max_available: Some(3),
+                min_level_difficulty: Some(10),
+                requires_unlock: true,
+                can_be_special: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let wire = Item {
+            id: "wire".to_owned(),
+            nameidentifier: Some("cable".to_owned()),
+            variant_of: Some("copper".to_owned()),
+            icon: Some(Icon {
+                texture: "Content/Items/wire.png".to_owned(),
+                source_rect: Some(Rect {
+                    x: 0,
+                    y: 64,
+                    width: 32,
+                    height: 32,
+                }),
+            }),
+            atlas_rect: Some(Rect {
+                x: 64,
+                y: 0,
+                width: 64,
+                height: 32,
+            }),
+            fabricate: vec![Fabricate {
+                suitable_fabricators: vec![Fabricator::Fabricator],
+                time: 10.0,
+                required_items: vec![
+                    RequiredItem {
+                        item: ItemRef::Id("copper".to_owned()),
+                        amount: 2,
+                        condition: Some(ConditionRange {
+                            min: Some(0.5),
+                            max: None,
+                        }),
+                    },
+                    RequiredItem {
+                        item: ItemRef::Tag("smallitem".to_owned()),
+                        amount: 1,
+                        condition: None,
+                    },
+                ],
+                required_skills: [(Skill::Electrical, 20)].into_iter().collect(),
+                requires_recipe: true,
+                out_condition: 0.9,
+                amount: 2,
+                recycle: false,
+            }],
+            deconstruct: vec![Deconstruct {
+                time: 5.0,
+                required_items: vec![],
+                required_skills: BTreeMap::new(),
+                items: vec![ProducedItem {
+                    id: "copper".to_owned(),
+                    amount: 1,
+                    mincondition: Some(0.1),
+                }],
+            }],
+            provenance: Some(Provenance {
+                package: "Better Wire".to_owned(),
+                overrides: vec!["Vanilla".to_owned()],
+                inherits: vec!["Vanilla".to_owned()],
+            }),
+            ..Default::default()
+        };
+
+        let texts = [
+            ("entityname.copper", "Copper"),
+            ("entityname.cable", "Wire"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect();
+        ItemDB {
+            texts: [(Language::English, texts)].into_iter().collect(),
+            items: vec![copper, wire],
+            packages: vec!["Vanilla".to_owned(), "Better Wire".to_owned()],
+            icon_atlas: Some(IconAtlas {
+                width: 128,
+                height: 64,
+            }),
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let itemdb = sample();
+        assert_eq!(PackedItemDB::pack(&itemdb).unpack().unwrap(), itemdb);
+        assert_eq!(decode(&encode(&itemdb).unwrap()).unwrap(), itemdb);
+    }
+
+    #[test]
+    fn stores_strings_once() {
+        let packed = PackedItemDB::pack(&sample());
+        let copper = packed.strings.iter().filter(|s| *s == "copper").count();
+        assert_eq!(copper, 1);
+    }
+
+    #[test]
+    fn rejects_bad_string_indexes() {
+        let mut packed = PackedItemDB::pack(&sample());
+        packed.items[0].id = packed.strings.len() as StringIndex;
+        assert!(matches!(
+            packed.unpack(),
+            Err(PackError::BadStringIndex(idx)) if idx as usize == packed.strings.len()
+        ));
+    }
+}
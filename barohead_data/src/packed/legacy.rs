@@ -0,0 +1,232 @@
+//! The formats published before the database had a header, so those files can
+//! still be read. The baseline wrote [`ItemDB`] as plain bincode, and then for
+//! a while it was packed without a header. Neither has changed since, and
+//! their shapes are kept here as they were, not as the current ones.
+
+use super::*;
+
+#[derive(Serialize, Deserialize)]
+struct PriceModifierV0 {
+    multiplier: Option<f32>,
+    sold: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PriceV0 {
+    baseprice: i32,
+    sold: bool,
+    modifiers: BTreeMap<StoreIdentifier, PriceModifierV0>,
+}
+
+impl From<PriceV0> for Price {
+    fn from(price: PriceV0) -> Self {
+        Price {
+            baseprice: price.baseprice,
+            sold: price.sold,
+            modifiers: price
+                .modifiers
+                .into_iter()
+                .map(|(store, modifier)| {
+                    let modifier = PriceModifier {
+                        multiplier: modifier.multiplier,
+                        sold: modifier.sold,
+                        ..Default::default()
+                    };
+                    (store, modifier)
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+// Plain bincode. Recipes are stored as they still are.
+#[derive(Serialize, Deserialize)]
+struct ItemV0 {
+    id: String,
+    nameidentifier: Option<String>,
+    fabricate: Vec<Fabricate>,
+    deconstruct: Vec<Deconstruct>,
+    price: Option<PriceV0>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ItemDBV0 {
+    texts: BTreeMap<Language, BTreeMap<String, String>>,
+    items: Vec<ItemV0>,
+}
+
+impl From<ItemDBV0> for ItemDB {
+    fn from(itemdb: ItemDBV0) -> Self {
+        ItemDB {
+            texts: itemdb.texts,
+            items: itemdb
+                .items
+                .into_iter()
+                .map(|item| Item {
+                    id: item.id,
+                    nameidentifier: item.nameidentifier,
+                    fabricate: item.fabricate,
+                    deconstruct: item.deconstruct,
+                    price: item.price.map(Price::from),
+                    ..Default::default()
+                })
+                .collect(),
+            packages: vec![],
+            icon_atlas: None,
+        }
+    }
+}
+
+// Packed, before the header. Recipes are packed as they still are.
+#[derive(Serialize, Deserialize)]
+struct ItemV1 {
+    id: StringIndex,
+    nameidentifier: Option<StringIndex>,
+    fabricate: Vec<PackedFabricate>,
+    deconstruct: Vec<PackedDeconstruct>,
+    price: Option<PriceV0>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ItemDBV1 {
+    strings: Vec<String>,
+    texts: BTreeMap<Language, Vec<(StringIndex, StringIndex)>>,
+    items: Vec<ItemV1>,
+}
+
+impl From<ItemDBV1> for PackedItemDB {
+    fn from(packed: ItemDBV1) -> Self {
+        PackedItemDB {
+            strings: packed.strings,
+            texts: packed.texts,
+            items: packed
+                .items
+                .into_iter()
+                .map(|item| PackedItem {
+                    id: item.id,
+                    nameidentifier: item.nameidentifier,
+                    variant_of: None,
+                    tags: vec![],
+                    categories: vec![],
+                    icon: None,
+                    atlas_rect: None,
+                    fabricate: item.fabricate,
+                    deconstruct: item.deconstruct,
+                    price: item.price.map(Price::from),
+                    provenance: None,
+                })
+                .collect(),
+            packages: vec![],
+            icon_atlas: None,
+        }
+    }
+}
+
+/// Read a file without a header, as bare packed data or as plain bincode.
+pub(super) fn decode(bytes: &[u8]) -> Option<ItemDB> {
+    let packed = inflate(bytes)
+        .ok()
+        .and_then(|inflated| bincode::deserialize::<ItemDBV1>(&inflated).ok())
+        .and_then(|packed| PackedItemDB::from(packed).unpack().ok());
+    packed.or_else(|| {
+        bincode::deserialize::<ItemDBV0>(bytes)
+            .ok()
+            .map(ItemDB::from)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // testdata/items.json as the baseline's pack-index wrote it, and as the
+    // first packing pack-index wrote it before there was a header.
+    const BINCODE: &[u8] = include_bytes!("../../testdata/baseline.bincode");
+    const PACKED: &[u8] = include_bytes!("../../testdata/packed-v1.bin");
+
+    fn expected() -> ItemDB {
+        let texts = [("entityname.copper", "Copper"), ("entityname.wire", "Wire")]
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect();
+        let copper = Item {
+            id: "copper".to_owned(),
+            price: Some(Price {
+                baseprice: 40,
+                sold: false,
+                modifiers: [(
+                    StoreIdentifier::MerchantMine,
+                    PriceModifier {
+                        multiplier: Some(0.8),
+                        sold: Some(true),
+                        ..Default::default()
+                    },
+                )]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let wire = Item {
+            id: "wire".to_owned(),
+            fabricate: vec![Fabricate {
+                suitable_fabricators: vec![Fabricator::Fabricator],
+                time: 10.0,
+                required_items: vec![
+                    RequiredItem {
+                        item: ItemRef::Id("copper".to_owned()),
+                        amount: 1,
+                        condition: None,
+                    },
+                    RequiredItem {
+                        item: ItemRef::Tag("smallitem".to_owned()),
+                        amount: 2,
+                        condition: Some(ConditionRange {
+                            min: Some(0.5),
+                            max: None,
+                        }),
+                    },
+                ],
+                required_skills: [(Skill::Electrical, 20)].into_iter().collect(),
+                requires_recipe: false,
+                out_condition: 1.0,
+                amount: 2,
+                recycle: false,
+            }],
+            deconstruct: vec![Deconstruct {
+                time: 5.0,
+                required_items: vec![],
+                required_skills: BTreeMap::new(),
+                items: vec![ProducedItem {
+                    id: "copper".to_owned(),
+                    amount: 1,
+                    mincondition: Some(0.1),
+                }],
+            }],
+            ..Default::default()
+        };
+        ItemDB {
+            texts: [(Language::English, texts)].into_iter().collect(),
+            items: vec![copper, wire],
+            packages: vec![],
+            icon_atlas: None,
+        }
+    }
+
+    #[test]
+    fn reads_baseline_bincode() {
+        assert_eq!(decode(BINCODE), Some(expected()));
+    }
+
+    #[test]
+    fn reads_packed_without_header() {
+        assert_eq!(decode(PACKED), Some(expected()));
+    }
+
+    #[test]
+    fn rejects_anything_else() {
+        assert_eq!(decode(b"not an item database"), None);
+    }
+}
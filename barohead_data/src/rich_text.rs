@@ -0,0 +1,140 @@
+//! The game's text markup.
+//!
+//! Texts can colour parts of themselves by wrapping them in tags between `‖`
+//! characters, as in `Deals ‖color:gui.red‖heavy‖end‖ damage`. Tags that
+//! aren't understood are dropped, keeping the text they wrap, so an odd
+//! description still reads correctly.
+
+/// A colour as written in a tag.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Color {
+    /// One of the game's named interface colours, such as `gui.orange`,
+    /// without the prefix.
+    Named(String),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some(name) = value.strip_prefix("gui.") {
+            return Some(Color::Named(name.to_ascii_lowercase()));
+        }
+        if let Some(hex) = value.strip_prefix('#') {
+            let channel = |idx: usize| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok();
+            return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+        }
+        // "r,g,b" or "r,g,b,a", ignoring the alpha.
+        let channels = value
+            .split(',')
+            .map(|channel| channel.trim().parse::<u8>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        match channels[..] {
+            [r, g, b] | [r, g, b, _] => Some(Color::Rgb(r, g, b)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub color: Option<Color>,
+}
+
+/// Text split into runs that share the same styling. Line breaks are left in
+/// the text as `\n`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RichText {
+    pub spans: Vec<Span>,
+}
+
+impl RichText {
+    pub fn parse(markup: &str) -> Self {
+        // Texts spell line breaks as a literal backslash-n.
+        let markup = markup.replace("\\n", "\n");
+
+        let mut rich_text = RichText::default();
+        let mut color = None;
+        let mut parts = markup.split('‖');
+        let mut text = parts.next().unwrap_or_default().to_owned();
+        // After the first part, parts alternate between a tag and the text
+        // that follows it. A trailing unpaired marker is kept as text.
+        while let Some(tag) = parts.next() {
+            let Some(following) = parts.next() else {
+                text.push('‖');
+                text.push_str(tag);
+                break;
+            };
+            rich_text.push(std::mem::take(&mut text), color.clone());
+            let (name, value) = tag.split_once(':').unwrap_or((tag, ""));
+            match name.trim().to_ascii_lowercase().as_str() {
+                "color" => color = Color::parse(value),
+                "end" => color = None,
+                _ => {}
+            }
+            text = following.to_owned();
+        }
+        rich_text.push(text, color);
+        rich_text
+    }
+
+    fn push(&mut self, text: String, color: Option<Color>) {
+        if text.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.color == color => last.text.push_str(&text),
+            _ => self.spans.push(Span { text, color }),
+        }
+    }
+
+    /// The text with all markup removed.
+    pub fn plain_text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str, color: Option<Color>) -> Span {
+        Span {
+            text: text.to_owned(),
+            color,
+        }
+    }
+
+    #[test]
+    fn parses_color_tags() {
+        let text = RichText::parse(
+            "Deals ‖color:gui.red‖heavy‖end‖ damage.\\nUse with ‖color:255,200,0‖care‖end‖.",
+        );
+        assert_eq!(
+            text.spans,
+            vec![
+                span("Deals ", None),
+                span("heavy", Some(Color::Named("red".to_owned()))),
+                span(" damage.\nUse with ", None),
+                span("care", Some(Color::Rgb(255, 200, 0))),
+                span(".", None),
+            ]
+        );
+        assert_eq!(text.plain_text(), "Deals heavy damage.\nUse with care.");
+    }
+
+    #[test]
+    fn tolerates_odd_markup() {
+        // Unknown tags vanish, unknown colours are plain, and a stray marker
+        // is just a character.
+        let text = RichText::parse("‖metadata:x‖Link‖end‖ ‖color:mauve‖a‖end‖ b‖c");
+        assert_eq!(text.spans, vec![span("Link a b‖c", None)]);
+
+        assert_eq!(
+            RichText::parse("‖color:#00ff80‖go").spans,
+            vec![span("go", Some(Color::Rgb(0, 255, 128)))]
+        );
+        assert!(RichText::parse("").spans.is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use barohead_data::items as data;
+
+use crate::{Error, FabricateRef, ItemRef, DB};
+
+/// Something a bill of materials bottoms out at: an item nobody fabricates,
+/// or a slot that accepts any item with a tag.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub enum Material {
+    Item(ItemRef),
+    Tag(String),
+}
+
+/// One fabrication recipe, run enough times to cover everything downstream.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BomStep {
+    pub fabricate_ref: FabricateRef,
+    /// How many times the recipe runs.
+    pub runs: i32,
+    /// How many of the item are needed, which can be less than the recipe
+    /// produces.
+    pub needed: i32,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct BillOfMaterials {
+    /// Ordered so that every step's ingredients are made by earlier steps.
+    pub steps: Vec<BomStep>,
+    pub materials: BTreeMap<Material, i32>,
+}
+
+// The recipe we'd use to make an item. Recycling recipes make it too, but
+// out of something else that was itself made from it, so they're a way to
+// get materials back rather than a way to make anything from scratch.
+pub(crate) fn chosen_recipe(item: &data::Item) -> Option<usize> {
+    item.fabricate
+        .iter()
+        .position(|fabricate| !fabricate.recycle)
+}
+
+struct Planner<'a> {
+    db: &'a DB,
+    /// Items to treat as raw materials even if they could be made.
+    bought: &'a BTreeSet<ItemRef>,
+    recipes: BTreeMap<ItemRef, usize>,
+    visited: BTreeSet<ItemRef>,
+    stack: BTreeSet<ItemRef>,
+    /// Items in an order where ingredients come before what they make.
+    order: Vec<ItemRef>,
+}
+
+impl<'a> Planner<'a> {
+    fn visit(&mut self, item_ref: ItemRef) -> Result<(), Error> {
+        if !self.visited.insert(item_ref) {
+            return Ok(());
+        }
+
+        let item = self.db.get_item(item_ref)?;
+        let recipe = chosen_recipe(item).filter(|_| !self.bought.contains(&item_ref));
+        if let Some(idx) = recipe {
+            let ingredients = item.fabricate[idx]
+                .required_items
+                .iter()
+                .filter_map(|required_item| match &required_item.item {
+                    data::ItemRef::Id(id) => Some(self.db.new_item_ref(id)),
+                    data::ItemRef::Tag(_) => None,
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // Something that's made out of itself, however indirectly, gets
+            // treated as a raw material where the loop closes.
+            if !ingredients
+                .iter()
+                .any(|ingredient| self.stack.contains(ingredient))
+            {
+                self.recipes.insert(item_ref, idx);
+                self.stack.insert(item_ref);
+                for ingredient in ingredients {
+                    self.visit(ingredient)?;
+                }
+                self.stack.remove(&item_ref);
+            }
+        }
+
+        self.order.push(item_ref);
+        Ok(())
+    }
+}
+
+impl DB {
+    /// Everything needed to fabricate the targets from scratch, always using
+    /// an item's first recipe that isn't for recycling.
+    pub fn bill_of_materials(&self, targets: &[(ItemRef, i32)]) -> Result<BillOfMaterials, Error> {
+        self.bill_of_materials_buying(targets, &BTreeSet::new())
+    }
+
+    /// Like [`DB::bill_of_materials`], but stopping at `bought` items as if
+    /// nobody could make them.
+    pub fn bill_of_materials_buying(
+        &self,
+        targets: &[(ItemRef, i32)],
+        bought: &BTreeSet<ItemRef>,
+    ) -> Result<BillOfMaterials, Error> {
+        let mut planner = Planner {
+            db: self,
+            bought,
+            recipes: BTreeMap::new(),
+            visited: BTreeSet::new(),
+            stack: BTreeSet::new(),
+            order: vec![],
+        };
+        for (item_ref, _) in targets {
+            planner.visit(*item_ref)?;
+        }
+
+        let mut needed: BTreeMap<ItemRef, i32> = BTreeMap::new();
+        for (item_ref, quantity) in targets {
+            *needed.entry(*item_ref).or_default() += quantity;
+        }
+
+        // Walk from the targets towards the raw materials, so that all the
+        // demand for an item is known before deciding how often to make it.
+        let mut bom = BillOfMaterials::default();
+        for item_ref in planner.order.iter().rev() {
+            let quantity = needed.get(item_ref).copied().unwrap_or(0);
+            if quantity <= 0 {
+                continue;
+            }
+
+            let Some(idx) = planner.recipes.get(item_ref) else {
+                *bom.materials.entry(Material::Item(*item_ref)).or_default() += quantity;
+                continue;
+            };
+
+            let fabricate_ref = FabricateRef {
+                item_ref: *item_ref,
+                idx: *idx,
+            };
+            let fabricate = self.get_fabricate(&fabricate_ref)?;
+            let runs = (quantity + fabricate.amount.max(1) - 1) / fabricate.amount.max(1);
+
+            for required_item in &fabricate.required_items {
+                let amount = required_item.amount * runs;
+                match &required_item.item {
+                    data::ItemRef::Id(id) => {
+                        *needed.entry(self.new_item_ref(id)?).or_default() += amount;
+                    }
+                    data::ItemRef::Tag(tag) => {
+                        *bom.materials.entry(Material::Tag(tag.clone())).or_default() += amount;
+                    }
+                }
+            }
+
+            bom.steps.push(BomStep {
+                fabricate_ref,
+                runs,
+                needed: quantity,
+            });
+        }
+
+        bom.steps.reverse();
+        Ok(bom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{self, db, fabricate};
+
+    use super::*;
+
+    fn item(id: &str, recipe: &[(&str, i32)], amount: i32) -> data::Item {
+        let mut item = test_support::item(id);
+        if !recipe.is_empty() {
+            item.fabricate.push(fabricate(recipe, amount));
+        }
+        item
+    }
+
+    #[test]
+    fn shares_intermediates_between_consumers() {
+        let db = db(vec![
+            item("copper", &[], 1),
+            item("wire", &[("copper", 1)], 2),
+            item("fuse", &[("wire", 1), ("#smallitem", 1)], 1),
+            item("battery", &[("wire", 1), ("copper", 2)], 1),
+        ]);
+        let copper = db.new_item_ref("copper").unwrap();
+        let wire = db.new_item_ref("wire").unwrap();
+        let fuse = db.new_item_ref("fuse").unwrap();
+        let battery = db.new_item_ref("battery").unwrap();
+
+        let bom = db.bill_of_materials(&[(fuse, 3), (battery, 1)]).unwrap();
+
+        // Four wires from two runs, rather than rounding up separately.
+        let wire_step = bom
+            .steps
+            .iter()
+            .find(|step| step.fabricate_ref.item_ref == wire)
+            .unwrap();
+        assert_eq!((wire_step.runs, wire_step.needed), (2, 4));
+        assert_eq!(bom.steps.first().unwrap().fabricate_ref.item_ref, wire);
+
+        assert_eq!(
+            bom.materials,
+            [
+                (Material::Item(copper), 4),
+                (Material::Tag("smallitem".to_owned()), 3),
+            ]
+            .into_iter()
+            .collect()
+        );
+    }
+
+    #[test]
+    fn breaks_loops() {
+        let db = db(vec![item("a", &[("b", 1)], 1), item("b", &[("a", 1)], 1)]);
+        let a = db.new_item_ref("a").unwrap();
+        let bom = db.bill_of_materials(&[(a, 1)]).unwrap();
+        assert_eq!(bom.steps.len(), 1);
+        assert_eq!(
+            bom.materials,
+            [(Material::Item(db.new_item_ref("b").unwrap()), 1)]
+                .into_iter()
+                .collect()
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use barohead_data::items::Category;
+
+use crate::{ItemRef, DB};
+
+impl DB {
+    /// Items in a category, ordered by name.
+    pub fn items_in_category(&self, category: Category) -> Vec<ItemRef> {
+        let mut items = self
+            .item_refs()
+            .filter(|item_ref| {
+                self.get_item(*item_ref)
+                    .is_ok_and(|item| item.categories.contains(&category))
+            })
+            .collect::<Vec<_>>();
+        items.sort_by_key(|item_ref| self.item_translations.get_name(item_ref).unwrap());
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use barohead_data::items::Item;
+
+    use crate::test_support::{self, itemdb};
+
+    use super::*;
+
+    fn item(id: &str, categories: &[Category]) -> Item {
+        Item {
+            categories: categories.to_vec(),
+            ..test_support::item(id)
+        }
+    }
+
+    #[test]
+    fn lists_items_by_name() {
+        let db = DB::new(itemdb(
+            vec![
+                item("fuelrod", &[Category::Material, Category::Fuel]),
+                item("copper", &[Category::Material]),
+                item("crate", &[]),
+            ],
+            &[
+                ("entityname.fuelrod", "Fuel Rod"),
+                ("entityname.copper", "Copper"),
+            ],
+        ))
+        .unwrap();
+        let fuelrod = db.new_item_ref("fuelrod").unwrap();
+        let copper = db.new_item_ref("copper").unwrap();
+
+        assert_eq!(
+            db.items_in_category(Category::Material),
+            vec![copper, fuelrod]
+        );
+        assert_eq!(db.items_in_category(Category::Fuel), vec![fuelrod]);
+        assert!(db.items_in_category(Category::Misc).is_empty());
+    }
+}
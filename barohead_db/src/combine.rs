@@ -0,0 +1,132 @@
+use std::collections::BTreeSet;
+
+use barohead_data::items as data;
+
+use crate::{Error, Node, ProcessRef, DB};
+
+/// A recipe that consumes every one of a set of ingredients.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Combination {
+    pub process: ProcessRef,
+    /// How many of the recipe's ingredients were asked about.
+    pub covered: usize,
+    /// How many different ingredients the recipe takes.
+    pub ingredients: usize,
+}
+
+impl Combination {
+    /// The share of the recipe's ingredients that were asked about, with 1
+    /// meaning nothing else is needed.
+    pub fn coverage(&self) -> f32 {
+        self.covered as f32 / self.ingredients as f32
+    }
+}
+
+fn required_items<'a>(db: &'a DB, process: &ProcessRef) -> Result<&'a [data::RequiredItem], Error> {
+    Ok(match process {
+        ProcessRef::Fabricate(fabricate_ref) => &db.get_fabricate(fabricate_ref)?.required_items,
+        ProcessRef::Deconstruct(deconstruct_ref) => {
+            &db.get_deconstruct(deconstruct_ref)?.required_items
+        }
+    })
+}
+
+impl DB {
+    /// Every recipe that consumes all of `wanted`, best covered first. Tags
+    /// only match recipes that ask for the tag itself, not for an item that
+    /// happens to carry it.
+    pub fn processes_using_all(&self, wanted: &[Node]) -> Result<Vec<Combination>, Error> {
+        let mut candidates: Option<BTreeSet<ProcessRef>> = None;
+        for node in wanted {
+            let used_by = match node {
+                Node::Item(item_ref) => self.get_used_by(*item_ref),
+                Node::Tag(tag) => self.get_tag_used_by(tag),
+            };
+            let used_by = used_by
+                .iter()
+                .flat_map(|refs| refs.iter().cloned())
+                .collect::<BTreeSet<_>>();
+            candidates = Some(match candidates {
+                Some(candidates) => candidates.intersection(&used_by).cloned().collect(),
+                None => used_by,
+            });
+        }
+
+        // Every candidate uses all of the wanted ingredients, so it's only
+        // the size of the recipe that differs.
+        let covered = wanted.iter().collect::<BTreeSet<_>>().len();
+        let mut combinations = vec![];
+        for process in candidates.unwrap_or_default() {
+            let ingredients = required_items(self, &process)?
+                .iter()
+                .map(|required_item| match &required_item.item {
+                    data::ItemRef::Id(id) => (false, id),
+                    data::ItemRef::Tag(tag) => (true, tag),
+                })
+                .collect::<BTreeSet<_>>()
+                .len();
+            combinations.push(Combination {
+                process,
+                covered,
+                ingredients,
+            });
+        }
+
+        // Fewest missing ingredients breaks ties between recipes that are
+        // equally covered, so small recipes come before large ones.
+        combinations.sort_by(|a, b| {
+            b.coverage()
+                .total_cmp(&a.coverage())
+                .then((a.ingredients - a.covered).cmp(&(b.ingredients - b.covered)))
+        });
+        Ok(combinations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{self, db, fabricate};
+
+    use super::*;
+
+    fn item(id: &str, recipe: &[&str]) -> data::Item {
+        let mut item = test_support::item(id);
+        if !recipe.is_empty() {
+            let recipe = recipe.iter().map(|id| (*id, 1)).collect::<Vec<_>>();
+            item.fabricate.push(fabricate(&recipe, 1));
+        }
+        item
+    }
+
+    #[test]
+    fn ranks_by_coverage() {
+        let db = db(vec![
+            item("copper", &[]),
+            item("tin", &[]),
+            item("wire", &["copper"]),
+            item("solder", &["copper", "tin"]),
+            item("battery", &["copper", "tin", "#chem"]),
+            item("fuse", &["copper", "tin", "copper"]),
+        ]);
+        let [copper, tin, solder, battery, fuse] =
+            ["copper", "tin", "solder", "battery", "fuse"].map(|id| db.new_item_ref(id).unwrap());
+
+        let made = |wanted: &[Node]| {
+            db.processes_using_all(wanted)
+                .unwrap()
+                .into_iter()
+                .map(|combination| (combination.process.item_ref(), combination.coverage()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            made(&[Node::Item(copper), Node::Item(tin)]),
+            vec![(solder, 1.0), (fuse, 1.0), (battery, 2.0 / 3.0)]
+        );
+        assert_eq!(
+            made(&[Node::Item(tin), Node::Tag("chem".to_owned())]),
+            vec![(battery, 2.0 / 3.0)]
+        );
+        assert!(made(&[]).is_empty());
+    }
+}
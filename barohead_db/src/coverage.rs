@@ -0,0 +1,158 @@
+use crate::{Error, ItemRef, StoreSummary, DB, INTERESTING_MERCHANTS};
+
+/// Whether any store will sell an item, for grouping items that otherwise
+/// look alike. Only stores a campaign can visit count, so not the tutorial's.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum StoreAvailability {
+    /// At least one store stocks it.
+    Sold,
+    /// It has a price, so stores will buy it, but none stock it.
+    NotSold,
+    /// It has no price at all.
+    Unpriced,
+}
+
+impl StoreAvailability {
+    pub const ALL: [StoreAvailability; 3] = [
+        StoreAvailability::Sold,
+        StoreAvailability::NotSold,
+        StoreAvailability::Unpriced,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StoreAvailability::Sold => "Sold in stores",
+            StoreAvailability::NotSold => "Priced, not sold",
+            StoreAvailability::Unpriced => "No price",
+        }
+    }
+}
+
+impl DB {
+    pub fn store_availability(&self, item_ref: ItemRef) -> Result<StoreAvailability, Error> {
+        let Some(price) = &self.get_item(item_ref)?.price else {
+            return Ok(StoreAvailability::Unpriced);
+        };
+        let sold = INTERESTING_MERCHANTS
+            .iter()
+            .any(|store| StoreSummary::for_store(price, *store).sell.is_some());
+        Ok(if sold {
+            StoreAvailability::Sold
+        } else {
+            StoreAvailability::NotSold
+        })
+    }
+
+    /// Whether an item can be fabricated, dropped by deconstructing
+    /// something, or bought.
+    pub fn is_obtainable(&self, item_ref: ItemRef) -> Result<bool, Error> {
+        let item = self.get_item(item_ref)?;
+        Ok(item.fabricate.iter().any(|fabricate| !fabricate.recycle)
+            || self.get_produced_by(item_ref).is_some()
+            || self.store_availability(item_ref)? == StoreAvailability::Sold)
+    }
+
+    /// Whether an item is an ingredient in anything, either by name or by
+    /// having a tag a recipe asks for.
+    pub fn is_used(&self, item_ref: ItemRef) -> Result<bool, Error> {
+        let item = self.get_item(item_ref)?;
+        Ok(self.get_used_by(item_ref).is_some()
+            || item
+                .tags
+                .iter()
+                .any(|tag| self.get_tag_used_by(tag).is_some()))
+    }
+
+    /// Items nothing in the database provides a way to get hold of.
+    pub fn unobtainable_items(&self) -> Result<Vec<ItemRef>, Error> {
+        let mut items = vec![];
+        for item_ref in self.item_refs() {
+            if !self.is_obtainable(item_ref)? {
+                items.push(item_ref);
+            }
+        }
+        Ok(items)
+    }
+
+    /// Items no recipe asks for, by name or by tag.
+    pub fn unused_items(&self) -> Result<Vec<ItemRef>, Error> {
+        let mut items = vec![];
+        for item_ref in self.item_refs() {
+            if !self.is_used(item_ref)? {
+                items.push(item_ref);
+            }
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use barohead_data::items::{Price, PriceModifier, StoreIdentifier};
+
+    use crate::test_support::{db, deconstruct, fabricate, item};
+
+    use super::*;
+
+    fn price(sold: bool) -> Option<Price> {
+        Some(Price {
+            baseprice: 10,
+            sold,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn classifies_items() {
+        let mut ore = item("ore");
+        ore.deconstruct.push(deconstruct(&[("copper", 1)]));
+        ore.price = price(false);
+        let mut wire = item("wire");
+        wire.fabricate.push(fabricate(&[("copper", 1)], 1));
+        wire.fabricate.push(fabricate(&[("#smallitem", 1)], 1));
+        let mut tool = item("tool");
+        tool.price = price(true);
+        tool.tags.push("smallitem".to_owned());
+        // Only the tutorial's merchant stocks it.
+        let mut manual = item("manual");
+        manual.price = Some(Price {
+            baseprice: 10,
+            sold: false,
+            modifiers: [(
+                StoreIdentifier::MerchantTutorial,
+                PriceModifier {
+                    sold: Some(true),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        });
+
+        let db = db(vec![item("copper"), ore, wire, tool, manual]);
+        let [copper, ore, wire, tool, manual] =
+            ["copper", "ore", "wire", "tool", "manual"].map(|id| db.new_item_ref(id).unwrap());
+
+        assert_eq!(db.unobtainable_items().unwrap(), vec![ore, manual]);
+        assert_eq!(db.unused_items().unwrap(), vec![ore, wire, manual]);
+        assert!(db.is_used(copper).unwrap());
+        assert!(db.is_used(tool).unwrap());
+        assert_eq!(
+            db.store_availability(ore).unwrap(),
+            StoreAvailability::NotSold
+        );
+        assert_eq!(
+            db.store_availability(tool).unwrap(),
+            StoreAvailability::Sold
+        );
+        assert_eq!(
+            db.store_availability(wire).unwrap(),
+            StoreAvailability::Unpriced
+        );
+        assert_eq!(
+            db.store_availability(manual).unwrap(),
+            StoreAvailability::NotSold
+        );
+    }
+}
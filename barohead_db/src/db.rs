@@ -0,0 +1,513 @@
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock};
+
+use barohead_data::items::{self as data, StoreIdentifier};
+use barohead_data::rich_text::RichText;
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+use crate::{DuplicationLoop, Error};
+
+// Statically compute a bunch of indexes and so on that we will use a bunch.
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SearchResult {
+    pub item_ref: ItemRef,
+    pub score: i64,
+    /// Character indices of the matched characters in the item name.
+    pub indices: Vec<usize>,
+}
+
+/// A handle to an item. Only meaningful for the [`DB`] that created it.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[repr(transparent)]
+pub struct ItemRef {
+    idx: u32,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct FabricateRef {
+    pub item_ref: ItemRef,
+    pub idx: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct DeconstructRef {
+    pub item_ref: ItemRef,
+    pub idx: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub enum ProcessRef {
+    Fabricate(FabricateRef),
+    Deconstruct(DeconstructRef),
+}
+
+impl ProcessRef {
+    /// The item the process belongs to: the output of a fabrication, or the
+    /// input of a deconstruction.
+    pub fn item_ref(&self) -> ItemRef {
+        match self {
+            ProcessRef::Fabricate(fabricate_ref) => fabricate_ref.item_ref,
+            ProcessRef::Deconstruct(deconstruct_ref) => deconstruct_ref.item_ref,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DB {
+    item_ids: BTreeMap<String, ItemRef>,
+
+    items: Vec<Arc<data::Item>>,
+    pub(crate) packages: Vec<String>,
+    icon_atlas: Option<data::IconAtlas>,
+    items_used_by: ProcessIndex,
+    items_produced_by: ProcessIndex,
+    tags_used_by: TagIndex,
+
+    pub item_translations: ItemTranslations,
+    item_descriptions: BTreeMap<ItemRef, RichText>,
+    pub store_translations: Translations<StoreIdentifier>,
+
+    pub(crate) duplication_loops: OnceLock<Result<Vec<DuplicationLoop>, Error>>,
+}
+
+type ProcessIndex = BTreeMap<ItemRef, Arc<Vec<ProcessRef>>>;
+type TagIndex = BTreeMap<String, Arc<Vec<ProcessRef>>>;
+
+/// Collects process references per item, ignoring duplicates.
+pub struct IndexBuilder<'a> {
+    item_ids: &'a BTreeMap<String, ItemRef>,
+    map: BTreeMap<ItemRef, Vec<ProcessRef>>,
+}
+
+impl<'a> IndexBuilder<'a> {
+    pub fn new(item_ids: &'a BTreeMap<String, ItemRef>) -> Self {
+        Self {
+            item_ids,
+            map: Default::default(),
+        }
+    }
+
+    pub fn add_reference(&mut self, id: &str, process_ref: &ProcessRef) -> Result<(), Error> {
+        let item_ref = *self
+            .item_ids
+            .get(id)
+            .ok_or_else(|| Error::UnknownItem(id.to_owned()))?;
+        let refs = self.map.entry(item_ref).or_default();
+        if !refs.contains(process_ref) {
+            refs.push(process_ref.clone())
+        }
+        Ok(())
+    }
+
+    pub fn extract(self) -> ProcessIndex {
+        self.map
+            .into_iter()
+            .map(|(id, refs)| (id, Arc::new(refs)))
+            .collect()
+    }
+}
+
+/// Build the used-by, produced-by and tag indexes for a set of items.
+pub fn build_indexes(
+    item_ids: &BTreeMap<String, ItemRef>,
+    items: &[Arc<data::Item>],
+) -> Result<(ProcessIndex, ProcessIndex, TagIndex), Error> {
+    let mut used_by_builder = IndexBuilder::new(item_ids);
+    let mut produced_by_builder = IndexBuilder::new(item_ids);
+    let mut tags_used_by: BTreeMap<String, Vec<ProcessRef>> = BTreeMap::new();
+
+    for (idx, item) in items.iter().enumerate() {
+        let item_ref = ItemRef { idx: idx as u32 };
+        for (idx, fabricate) in item.fabricate.iter().enumerate() {
+            let process_ref = ProcessRef::Fabricate(FabricateRef { item_ref, idx });
+
+            for required_item in &fabricate.required_items {
+                match &required_item.item {
+                    data::ItemRef::Id(id) => used_by_builder.add_reference(id, &process_ref)?,
+                    data::ItemRef::Tag(tag) => {
+                        let refs = tags_used_by.entry(tag.clone()).or_default();
+                        if !refs.contains(&process_ref) {
+                            refs.push(process_ref.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        for (idx, deconstruct) in item.deconstruct.iter().enumerate() {
+            let process_ref = ProcessRef::Deconstruct(DeconstructRef { item_ref, idx });
+
+            for required_item in &deconstruct.required_items {
+                if let data::ItemRef::Id(id) = &required_item.item {
+                    used_by_builder.add_reference(id, &process_ref)?;
+                }
+            }
+
+            for produced_item in &deconstruct.items {
+                produced_by_builder.add_reference(&produced_item.id, &process_ref)?;
+            }
+        }
+    }
+
+    let tags_used_by = tags_used_by
+        .into_iter()
+        .map(|(tag, refs)| (tag, Arc::new(refs)))
+        .collect();
+
+    // Throw away the boxes,
+    Ok((
+        used_by_builder.extract(),
+        produced_by_builder.extract(),
+        tags_used_by,
+    ))
+}
+
+impl DB {
+    /// Index an item database. Fails if the English texts are missing, or if
+    /// a recipe refers to an item that doesn't exist.
+    pub fn new(mut itemdb: data::ItemDB) -> Result<Self, Error> {
+        let items: Vec<Arc<data::Item>> = itemdb.items.into_iter().map(Arc::new).collect();
+
+        let item_ids: BTreeMap<String, ItemRef> = items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| (item.id.clone(), ItemRef { idx: idx as u32 }))
+            .collect();
+
+        let english_texts: BTreeMap<String, Arc<String>> = itemdb
+            .texts
+            .remove(&data::Language::English)
+            .ok_or(Error::MissingLanguage(data::Language::English))?
+            .into_iter()
+            .map(|(key, translation)| (key, Arc::from(translation)))
+            .collect();
+
+        let item_translations = items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                let name_key = item.name_text_key();
+                let name = english_texts
+                    .get(&name_key)
+                    .cloned()
+                    .unwrap_or_else(|| Arc::new(item.id.clone()));
+                (ItemRef { idx: idx as u32 }, name)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let item_descriptions = items
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, item)| {
+                let description = english_texts.get(&item.description_text_key())?;
+                Some((ItemRef { idx: idx as u32 }, RichText::parse(description)))
+            })
+            .collect();
+
+        let (items_used_by, items_produced_by, tags_used_by) = build_indexes(&item_ids, &items)?;
+
+        let store_translations = StoreIdentifier::ALL
+            .iter()
+            .map(|store_identifier| {
+                let name_key = store_identifier.name_text_key();
+                let name = english_texts
+                    .get(&name_key)
+                    .cloned()
+                    .unwrap_or_else(|| Arc::new(format!("{:#?}", store_identifier)));
+                (*store_identifier, name)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        Ok(Self {
+            item_ids,
+            items,
+            packages: itemdb.packages,
+            icon_atlas: itemdb.icon_atlas,
+            item_translations: ItemTranslations {
+                translations: Translations {
+                    translations: item_translations,
+                },
+            },
+            item_descriptions,
+            items_used_by,
+            items_produced_by,
+            tags_used_by,
+
+            store_translations: Translations {
+                translations: store_translations,
+            },
+
+            duplication_loops: OnceLock::new(),
+        })
+    }
+
+    /// Fuzzy search item names, best match first.
+    pub fn search(&self, query: &str) -> Vec<SearchResult> {
+        let matcher = SkimMatcherV2::default();
+        let mut matching_items: Vec<_> = self
+            .item_translations
+            .translations
+            .translations
+            .iter()
+            .filter_map(|(item_ref, description)| {
+                matcher
+                    .fuzzy_indices(description, query)
+                    .map(|(score, indices)| SearchResult {
+                        item_ref: *item_ref,
+                        score,
+                        indices,
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        matching_items.sort_by_key(|result| std::cmp::Reverse(result.score));
+
+        matching_items
+    }
+
+    pub fn item_refs(&self) -> impl Iterator<Item = ItemRef> + '_ {
+        (0..self.items.len()).map(|idx| ItemRef { idx: idx as u32 })
+    }
+
+    /// Every tag that some recipe accepts.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags_used_by.keys().map(String::as_str)
+    }
+
+    pub fn get_item(&self, item_ref: ItemRef) -> Result<&data::Item, Error> {
+        self.items
+            .get(item_ref.idx as usize)
+            .map(|item| item.as_ref())
+            .ok_or(Error::InvalidRef)
+    }
+
+    /// What the game says about an item, if it says anything.
+    pub fn item_description(&self, item_ref: ItemRef) -> Option<&RichText> {
+        self.item_descriptions.get(&item_ref)
+    }
+
+    /// The image holding every item's icon, if the database was packed with
+    /// one. Each item's place in it is its `atlas_rect`.
+    pub fn icon_atlas(&self) -> Option<&data::IconAtlas> {
+        self.icon_atlas.as_ref()
+    }
+
+    pub fn new_item_ref(&self, id_str: &str) -> Result<ItemRef, Error> {
+        self.item_ids
+            .get(id_str)
+            .copied()
+            .ok_or_else(|| Error::UnknownItem(id_str.to_owned()))
+    }
+
+    pub fn get_fabricate<'a>(
+        &'a self,
+        fabricate_ref: &FabricateRef,
+    ) -> Result<&'a data::Fabricate, Error> {
+        let item = self.get_item(fabricate_ref.item_ref)?;
+        item.fabricate
+            .get(fabricate_ref.idx)
+            .ok_or(Error::InvalidRef)
+    }
+
+    pub fn get_deconstruct<'a>(
+        &'a self,
+        deconstruct_ref: &DeconstructRef,
+    ) -> Result<&'a data::Deconstruct, Error> {
+        let item = self.get_item(deconstruct_ref.item_ref)?;
+        item.deconstruct
+            .get(deconstruct_ref.idx)
+            .ok_or(Error::InvalidRef)
+    }
+
+    /// Processes that consume this item.
+    pub fn get_used_by(&self, item_ref: ItemRef) -> Option<Arc<Vec<ProcessRef>>> {
+        self.items_used_by.get(&item_ref).cloned()
+    }
+
+    /// Processes that output this item. Fabrication of the item itself isn't
+    /// included, see [`data::Item::fabricate`].
+    pub fn get_produced_by(&self, item_ref: ItemRef) -> Option<Arc<Vec<ProcessRef>>> {
+        self.items_produced_by.get(&item_ref).cloned()
+    }
+
+    /// Processes that accept any item with this tag.
+    pub fn get_tag_used_by(&self, tag: &str) -> Option<Arc<Vec<ProcessRef>>> {
+        self.tags_used_by.get(tag).cloned()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Translations<T> {
+    translations: BTreeMap<T, Arc<String>>,
+}
+
+impl<T: Ord> Translations<T> {
+    pub fn get_name(&self, key: &T) -> Result<&str, Error> {
+        self.translations
+            .get(key)
+            .map(|name| name.as_str())
+            .ok_or(Error::InvalidRef)
+    }
+
+    pub fn get_name_arc(&self, key: &T) -> Result<Arc<String>, Error> {
+        self.translations.get(key).cloned().ok_or(Error::InvalidRef)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ItemTranslations {
+    translations: Translations<ItemRef>,
+}
+
+impl ItemTranslations {
+    pub fn get_name(&self, item_ref: impl Borrow<ItemRef>) -> Result<&str, Error> {
+        self.translations.get_name(item_ref.borrow())
+    }
+
+    pub fn get_name_arc(&self, item_ref: impl Borrow<ItemRef>) -> Result<Arc<String>, Error> {
+        self.translations.get_name_arc(item_ref.borrow())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data::{ItemDB, Language};
+
+    use crate::test_support::{deconstruct, fabricate, item, itemdb};
+
+    use super::*;
+
+    fn fixture() -> ItemDB {
+        let mut wire = item("wire");
+        wire.fabricate.push(data::Fabricate {
+            time: 10.0,
+            ..fabricate(&[("copper", 2), ("#smallitem", 1)], 1)
+        });
+        wire.deconstruct.push(deconstruct(&[("copper", 1)]));
+        itemdb(
+            vec![item("copper"), wire],
+            &[
+                ("entityname.copper", "Copper"),
+                ("entityname.wire", "Wire"),
+                (
+                    "entitydescription.wire",
+                    "Carries ‖color:gui.orange‖power‖end‖.",
+                ),
+                ("storename.merchantcity", "City"),
+            ],
+        )
+    }
+
+    #[test]
+    fn looks_up_items_by_id() {
+        let db = DB::new(fixture()).unwrap();
+        let wire = db.new_item_ref("wire").unwrap();
+        assert_eq!(db.get_item(wire).unwrap().id, "wire");
+        assert_eq!(db.item_translations.get_name(wire).unwrap(), "Wire");
+        assert_eq!(
+            db.item_description(wire).unwrap().plain_text(),
+            "Carries power."
+        );
+        let copper = db.new_item_ref("copper").unwrap();
+        assert_eq!(db.item_description(copper), None);
+        assert_eq!(
+            db.new_item_ref("tin"),
+            Err(Error::UnknownItem("tin".to_owned()))
+        );
+    }
+
+    #[test]
+    fn indexes_used_by_and_produced_by() {
+        let db = DB::new(fixture()).unwrap();
+        let copper = db.new_item_ref("copper").unwrap();
+        let wire = db.new_item_ref("wire").unwrap();
+
+        let used_by = db.get_used_by(copper).unwrap();
+        assert_eq!(
+            *used_by,
+            vec![ProcessRef::Fabricate(FabricateRef {
+                item_ref: wire,
+                idx: 0
+            })]
+        );
+
+        let produced_by = db.get_produced_by(copper).unwrap();
+        assert_eq!(
+            *produced_by,
+            vec![ProcessRef::Deconstruct(DeconstructRef {
+                item_ref: wire,
+                idx: 0
+            })]
+        );
+
+        assert_eq!(db.get_used_by(wire), None);
+        assert_eq!(db.tags().collect::<Vec<_>>(), vec!["smallitem"]);
+        assert_eq!(db.get_tag_used_by("smallitem").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn fetches_recipes() {
+        let db = DB::new(fixture()).unwrap();
+        let wire = db.new_item_ref("wire").unwrap();
+        let fabricate_ref = FabricateRef {
+            item_ref: wire,
+            idx: 0,
+        };
+        assert_eq!(db.get_fabricate(&fabricate_ref).unwrap().time, 10.0);
+        assert_eq!(
+            db.get_fabricate(&FabricateRef {
+                item_ref: wire,
+                idx: 1
+            }),
+            Err(Error::InvalidRef)
+        );
+    }
+
+    #[test]
+    fn rejects_dangling_references() {
+        let mut itemdb = fixture();
+        itemdb.items.remove(0);
+        assert_eq!(
+            DB::new(itemdb),
+            Err(Error::UnknownItem("copper".to_owned()))
+        );
+    }
+
+    #[test]
+    fn requires_english() {
+        let mut itemdb = fixture();
+        itemdb.texts.clear();
+        assert_eq!(
+            DB::new(itemdb),
+            Err(Error::MissingLanguage(Language::English))
+        );
+    }
+
+    #[test]
+    fn searches_names() {
+        let db = DB::new(fixture()).unwrap();
+        let results = db.search("cop");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item_ref, db.new_item_ref("copper").unwrap());
+        assert_eq!(results[0].indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn falls_back_to_ids_for_names() {
+        let mut itemdb = fixture();
+        itemdb.items.push(item("unnamed"));
+        let db = DB::new(itemdb).unwrap();
+        let unnamed = db.new_item_ref("unnamed").unwrap();
+        assert_eq!(db.item_translations.get_name(unnamed).unwrap(), "unnamed");
+        assert_eq!(
+            db.store_translations
+                .get_name(&StoreIdentifier::MerchantCity)
+                .unwrap(),
+            "City"
+        );
+    }
+}
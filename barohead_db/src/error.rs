@@ -0,0 +1,25 @@
+use std::fmt;
+
+use barohead_data::items::Language;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The database has no texts for a language we need.
+    MissingLanguage(Language),
+    /// No item with this identifier exists.
+    UnknownItem(String),
+    /// A reference that doesn't point into this database.
+    InvalidRef,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingLanguage(language) => write!(f, "no texts for {:?}", language),
+            Error::UnknownItem(id) => write!(f, "unknown item: {}", id),
+            Error::InvalidRef => write!(f, "reference does not belong to this database"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,476 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write;
+
+use barohead_data::items as data;
+
+use crate::{DeconstructRef, Error, FabricateRef, ItemRef, ProcessRef, DB};
+
+/// A vertex in the recipe graph. Tags stand in for "any item with this tag".
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub enum Node {
+    Item(ItemRef),
+    Tag(String),
+}
+
+/// One input or output of a process: something flowing from `from` to `to`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct Edge {
+    pub from: Node,
+    pub to: Node,
+    /// How many of `from` the process takes.
+    pub consumed: i32,
+    /// How many of `to` the process gives.
+    pub produced: i32,
+    pub process: ProcessRef,
+}
+
+impl Edge {
+    pub fn label(&self) -> String {
+        format!("{} → {}", self.consumed, self.produced)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RecipeGraph {
+    pub nodes: BTreeSet<Node>,
+    pub edges: BTreeSet<Edge>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+    GraphMl,
+}
+
+impl GraphFormat {
+    pub const ALL: [GraphFormat; 3] =
+        [GraphFormat::Dot, GraphFormat::Mermaid, GraphFormat::GraphMl];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Mermaid => "mermaid",
+            GraphFormat::GraphMl => "graphml",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Mermaid => "mmd",
+            GraphFormat::GraphMl => "graphml",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "text/vnd.graphviz",
+            GraphFormat::Mermaid => "text/plain",
+            GraphFormat::GraphMl => "application/graphml+xml",
+        }
+    }
+}
+
+fn required_node(db: &DB, required_item: &data::RequiredItem) -> Result<Node, Error> {
+    Ok(match &required_item.item {
+        data::ItemRef::Id(id) => Node::Item(db.new_item_ref(id)?),
+        data::ItemRef::Tag(tag) => Node::Tag(tag.clone()),
+    })
+}
+
+impl RecipeGraph {
+    fn add_edge(&mut self, from: Node, to: Node, amounts: (i32, i32), process: &ProcessRef) {
+        self.nodes.insert(from.clone());
+        self.nodes.insert(to.clone());
+        self.edges.insert(Edge {
+            from,
+            to,
+            consumed: amounts.0,
+            produced: amounts.1,
+            process: process.clone(),
+        });
+    }
+
+    // Add the edges for one process, returning its (inputs, outputs).
+    fn add_process(
+        &mut self,
+        db: &DB,
+        process_ref: &ProcessRef,
+    ) -> Result<(Vec<Node>, Vec<Node>), Error> {
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        match process_ref {
+            ProcessRef::Fabricate(fabricate_ref) => {
+                let fabricate = db.get_fabricate(fabricate_ref)?;
+                let product = Node::Item(fabricate_ref.item_ref);
+                for required_item in &fabricate.required_items {
+                    let input = required_node(db, required_item)?;
+                    self.add_edge(
+                        input.clone(),
+                        product.clone(),
+                        (required_item.amount, fabricate.amount),
+                        process_ref,
+                    );
+                    inputs.push(input);
+                }
+                outputs.push(product);
+            }
+            ProcessRef::Deconstruct(deconstruct_ref) => {
+                let deconstruct = db.get_deconstruct(deconstruct_ref)?;
+                let source = Node::Item(deconstruct_ref.item_ref);
+                for produced_item in &deconstruct.items {
+                    let output = Node::Item(db.new_item_ref(&produced_item.id)?);
+                    self.add_edge(
+                        source.clone(),
+                        output.clone(),
+                        (1, produced_item.amount),
+                        process_ref,
+                    );
+                    outputs.push(output);
+                }
+                inputs.push(source);
+            }
+        }
+        Ok((inputs, outputs))
+    }
+
+    /// The whole database.
+    pub fn all(db: &DB) -> Result<Self, Error> {
+        let mut graph = Self::default();
+        for item_ref in db.item_refs() {
+            for process_ref in own_processes(db, item_ref)? {
+                graph.add_process(db, &process_ref)?;
+            }
+        }
+        Ok(graph)
+    }
+
+    /// The processes around an item: `upstream` steps of how it's made, and
+    /// `downstream` steps of what it's made into.
+    pub fn around(
+        db: &DB,
+        item_ref: ItemRef,
+        upstream: usize,
+        downstream: usize,
+    ) -> Result<Self, Error> {
+        let mut graph = Self::default();
+        graph.nodes.insert(Node::Item(item_ref));
+
+        let mut visited = BTreeSet::from([item_ref]);
+        let mut frontier = vec![item_ref];
+        for _ in 0..upstream {
+            let mut next = vec![];
+            for item_ref in frontier {
+                let item = db.get_item(item_ref)?;
+                let fabricates = (0..item.fabricate.len())
+                    .map(|idx| ProcessRef::Fabricate(FabricateRef { item_ref, idx }));
+                let produced_by = db.get_produced_by(item_ref).unwrap_or_default();
+                for process_ref in fabricates.chain(produced_by.iter().cloned()) {
+                    let (inputs, _) = graph.add_process(db, &process_ref)?;
+                    next.extend(unvisited(&mut visited, inputs));
+                }
+            }
+            frontier = next;
+        }
+
+        let mut visited = BTreeSet::from([item_ref]);
+        let mut frontier = vec![item_ref];
+        for _ in 0..downstream {
+            let mut next = vec![];
+            for item_ref in frontier {
+                let item = db.get_item(item_ref)?;
+                let deconstructs = (0..item.deconstruct.len())
+                    .map(|idx| ProcessRef::Deconstruct(DeconstructRef { item_ref, idx }));
+                let used_by = db.get_used_by(item_ref).unwrap_or_default();
+                for process_ref in deconstructs.chain(used_by.iter().cloned()) {
+                    let (_, outputs) = graph.add_process(db, &process_ref)?;
+                    next.extend(unvisited(&mut visited, outputs));
+                }
+            }
+            frontier = next;
+        }
+
+        Ok(graph)
+    }
+
+    /// Add everything from another graph.
+    pub fn merge(&mut self, other: RecipeGraph) {
+        self.nodes.extend(other.nodes);
+        self.edges.extend(other.edges);
+    }
+
+    /// How many steps each node is downstream of `center`, negative for
+    /// upstream, following edges in either direction. Nodes that can't be
+    /// reached from `center` are left out.
+    pub fn layers(&self, center: &Node) -> BTreeMap<Node, i32> {
+        let mut layers = BTreeMap::from([(center.clone(), 0)]);
+        let mut queue = VecDeque::from([center.clone()]);
+        while let Some(node) = queue.pop_front() {
+            let layer = layers[&node];
+            for edge in &self.edges {
+                let next = if edge.from == node {
+                    (&edge.to, layer + 1)
+                } else if edge.to == node {
+                    (&edge.from, layer - 1)
+                } else {
+                    continue;
+                };
+                if !layers.contains_key(next.0) {
+                    layers.insert(next.0.clone(), next.1);
+                    queue.push_back(next.0.clone());
+                }
+            }
+        }
+        layers
+    }
+
+    fn node_label(db: &DB, node: &Node) -> Result<String, Error> {
+        Ok(match node {
+            Node::Item(item_ref) => db.item_translations.get_name(item_ref)?.to_owned(),
+            Node::Tag(tag) => format!("any {}", tag),
+        })
+    }
+
+    fn node_id(db: &DB, node: &Node) -> Result<String, Error> {
+        Ok(match node {
+            Node::Item(item_ref) => db.get_item(*item_ref)?.id.clone(),
+            Node::Tag(tag) => format!("tag:{}", tag),
+        })
+    }
+
+    pub fn render(&self, db: &DB, format: GraphFormat) -> Result<String, Error> {
+        match format {
+            GraphFormat::Dot => self.render_dot(db),
+            GraphFormat::Mermaid => self.render_mermaid(db),
+            GraphFormat::GraphMl => self.render_graphml(db),
+        }
+    }
+
+    // Fabrication is drawn solid and deconstruction dashed throughout.
+    fn render_dot(&self, db: &DB) -> Result<String, Error> {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+
+        let mut out = String::from("digraph barohead {\n  rankdir=LR;\n");
+        for node in &self.nodes {
+            let shape = match node {
+                Node::Item(_) => "box",
+                Node::Tag(_) => "ellipse",
+            };
+            writeln!(
+                out,
+                "  {} [label={}, shape={}];",
+                quote(&Self::node_id(db, node)?),
+                quote(&Self::node_label(db, node)?),
+                shape
+            )
+            .unwrap();
+        }
+        for edge in &self.edges {
+            let style = match edge.process {
+                ProcessRef::Fabricate(_) => "solid",
+                ProcessRef::Deconstruct(_) => "dashed",
+            };
+            writeln!(
+                out,
+                "  {} -> {} [label=\"{}\", style={}];",
+                quote(&Self::node_id(db, &edge.from)?),
+                quote(&Self::node_id(db, &edge.to)?),
+                edge.label(),
+                style
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+        Ok(out)
+    }
+
+    fn render_mermaid(&self, db: &DB) -> Result<String, Error> {
+        // Mermaid ids are restrictive, so number the nodes instead.
+        let ids = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (node, format!("n{}", idx)))
+            .collect::<BTreeMap<_, _>>();
+        let quote = |s: &str| format!("\"{}\"", s.replace('"', "#quot;"));
+
+        let mut out = String::from("flowchart LR\n");
+        for (node, id) in &ids {
+            let label = quote(&Self::node_label(db, node)?);
+            match node {
+                Node::Item(_) => writeln!(out, "  {}[{}]", id, label).unwrap(),
+                Node::Tag(_) => writeln!(out, "  {}([{}])", id, label).unwrap(),
+            }
+        }
+        for edge in &self.edges {
+            let arrow = match edge.process {
+                ProcessRef::Fabricate(_) => "-->",
+                ProcessRef::Deconstruct(_) => "-.->",
+            };
+            writeln!(
+                out,
+                "  {} {}|{}| {}",
+                ids[&edge.from],
+                arrow,
+                edge.label(),
+                ids[&edge.to]
+            )
+            .unwrap();
+        }
+        Ok(out)
+    }
+
+    fn render_graphml(&self, db: &DB) -> Result<String, Error> {
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        };
+
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <key id=\"consumed\" for=\"edge\" attr.name=\"consumed\" attr.type=\"int\"/>\n",
+            "  <key id=\"produced\" for=\"edge\" attr.name=\"produced\" attr.type=\"int\"/>\n",
+            "  <key id=\"process\" for=\"edge\" attr.name=\"process\" attr.type=\"string\"/>\n",
+            "  <graph id=\"barohead\" edgedefault=\"directed\">\n",
+        ));
+        for node in &self.nodes {
+            let kind = match node {
+                Node::Item(_) => "item",
+                Node::Tag(_) => "tag",
+            };
+            writeln!(
+                out,
+                "    <node id=\"{}\"><data key=\"label\">{}</data><data key=\"kind\">{}</data></node>",
+                escape(&Self::node_id(db, node)?),
+                escape(&Self::node_label(db, node)?),
+                kind
+            )
+            .unwrap();
+        }
+        for edge in &self.edges {
+            let process = match edge.process {
+                ProcessRef::Fabricate(_) => "fabricate",
+                ProcessRef::Deconstruct(_) => "deconstruct",
+            };
+            writeln!(
+                out,
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"consumed\">{}</data><data key=\"produced\">{}</data><data key=\"process\">{}</data></edge>",
+                escape(&Self::node_id(db, &edge.from)?),
+                escape(&Self::node_id(db, &edge.to)?),
+                edge.consumed,
+                edge.produced,
+                process
+            )
+            .unwrap();
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        Ok(out)
+    }
+}
+
+fn own_processes(db: &DB, item_ref: ItemRef) -> Result<Vec<ProcessRef>, Error> {
+    let item = db.get_item(item_ref)?;
+    let fabricates =
+        (0..item.fabricate.len()).map(|idx| ProcessRef::Fabricate(FabricateRef { item_ref, idx }));
+    let deconstructs = (0..item.deconstruct.len())
+        .map(|idx| ProcessRef::Deconstruct(DeconstructRef { item_ref, idx }));
+    Ok(fabricates.chain(deconstructs).collect())
+}
+
+// Tags aren't expanded, there's no way to walk on from "any item".
+fn unvisited(visited: &mut BTreeSet<ItemRef>, nodes: Vec<Node>) -> Vec<ItemRef> {
+    nodes
+        .into_iter()
+        .filter_map(|node| match node {
+            Node::Item(item_ref) => visited.insert(item_ref).then_some(item_ref),
+            Node::Tag(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{deconstruct, fabricate, item, itemdb};
+
+    use super::*;
+
+    fn fixture() -> DB {
+        let mut wire = item("wire");
+        wire.fabricate
+            .push(fabricate(&[("copper", 2), ("#smallitem", 1)], 4));
+        let mut fuse = item("fuse");
+        fuse.deconstruct.push(deconstruct(&[("wire", 3)]));
+        DB::new(itemdb(
+            vec![item("copper"), wire, fuse],
+            &[("entityname.copper", "Copper \"Cu\"")],
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn walks_a_limited_depth() {
+        let db = fixture();
+        let wire = db.new_item_ref("wire").unwrap();
+
+        let graph = RecipeGraph::around(&db, wire, 1, 0).unwrap();
+        // Made from copper and any small item, and from deconstructing fuses.
+        assert_eq!(graph.edges.len(), 3);
+        assert_eq!(graph.nodes.len(), 4);
+
+        let graph = RecipeGraph::around(&db, wire, 0, 0).unwrap();
+        assert!(graph.edges.is_empty());
+        assert_eq!(graph.nodes.len(), 1);
+
+        let copper = db.new_item_ref("copper").unwrap();
+        let graph = RecipeGraph::around(&db, copper, 0, 2).unwrap();
+        assert_eq!(graph.edges.len(), 2);
+    }
+
+    #[test]
+    fn lays_out_around_an_item() {
+        let db = fixture();
+        let copper = Node::Item(db.new_item_ref("copper").unwrap());
+        let wire = Node::Item(db.new_item_ref("wire").unwrap());
+        let fuse = Node::Item(db.new_item_ref("fuse").unwrap());
+
+        let mut graph = RecipeGraph::around(&db, db.new_item_ref("wire").unwrap(), 1, 0).unwrap();
+        let layers = graph.layers(&wire);
+        assert_eq!(layers[&copper], -1);
+        assert_eq!(layers[&fuse], -1);
+        assert_eq!(layers[&Node::Tag("smallitem".to_owned())], -1);
+
+        graph.edges.retain(|edge| edge.from != fuse);
+        assert!(!graph.layers(&wire).contains_key(&fuse));
+        assert_eq!(graph.layers(&copper)[&wire], 1);
+    }
+
+    #[test]
+    fn renders_each_format() {
+        let db = fixture();
+        let graph = RecipeGraph::all(&db).unwrap();
+
+        let dot = graph.render(&db, GraphFormat::Dot).unwrap();
+        assert!(dot.contains("\"copper\" [label=\"Copper \\\"Cu\\\"\", shape=box];"));
+        assert!(dot.contains("\"fuse\" -> \"wire\" [label=\"1 → 3\", style=dashed];"));
+        assert!(dot.contains("\"copper\" -> \"wire\" [label=\"2 → 4\", style=solid];"));
+
+        let mermaid = graph.render(&db, GraphFormat::Mermaid).unwrap();
+        assert!(mermaid.contains("[\"Copper #quot;Cu#quot;\"]"));
+        assert!(mermaid.contains("-.->|1 → 3|"));
+
+        let graphml = graph.render(&db, GraphFormat::GraphMl).unwrap();
+        assert!(graphml.contains("<node id=\"tag:smallitem\">"));
+        assert!(graphml.contains("Copper &quot;Cu&quot;"));
+        assert!(graphml.contains("<data key=\"consumed\">2</data><data key=\"produced\">4</data>"));
+    }
+}
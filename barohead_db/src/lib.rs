@@ -0,0 +1,49 @@
+//! Indexes and queries over a [`barohead_data::items::ItemDB`].
+//!
+//! This is everything the website knows about items, without any of the
+//! website, so that other tools can give the same answers.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let bytes = std::fs::read("recipes.bincode")?;
+//! let (_header, itemdb) = barohead_data::database::read(&bytes)?;
+//! let db = barohead_db::DB::new(itemdb)?;
+//!
+//! let copper = db.new_item_ref("copper")?;
+//! for process_ref in db.get_used_by(copper).iter().flat_map(|refs| refs.iter()) {
+//!     println!("{}", db.item_translations.get_name(process_ref.item_ref())?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod bom;
+mod categories;
+mod combine;
+mod coverage;
+mod db;
+mod error;
+mod graph;
+mod loops;
+mod mods;
+mod plan;
+mod pricing;
+mod salvage;
+mod schedule;
+#[cfg(any(test, feature = "test-support"))]
+#[doc(hidden)]
+pub mod test_support;
+mod variants;
+
+pub use bom::{BillOfMaterials, BomStep, Material};
+pub use combine::Combination;
+pub use coverage::StoreAvailability;
+pub use db::*;
+pub use error::Error;
+pub use graph::{Edge, GraphFormat, Node, RecipeGraph};
+pub use loops::{DuplicationLoop, LoopStep, MAX_LOOP_STEPS};
+pub use mods::ModFilter;
+pub use plan::{ProductionPlan, Purchase};
+pub use pricing::{Campaign, Stock, StoreSummary, INTERESTING_MERCHANTS};
+pub use salvage::SalvageValue;
+pub use schedule::{fabrication_time, Schedule, ScheduledRun};
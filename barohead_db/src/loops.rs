@@ -0,0 +1,338 @@
+use std::collections::BTreeMap;
+
+use barohead_data::items as data;
+
+use crate::{DeconstructRef, Error, FabricateRef, ItemRef, Node, ProcessRef, DB};
+
+/// Longer loops are possible in principle, but the search gets expensive and
+/// they haven't turned up in practice.
+pub const MAX_LOOP_STEPS: usize = 4;
+
+/// One process in a loop, turning some of `input` into some of `output`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LoopStep {
+    pub process: ProcessRef,
+    pub input: ItemRef,
+    pub output: ItemRef,
+    /// How many times the process runs per trip around the loop, so that
+    /// each step uses exactly what the previous one made.
+    pub runs: f32,
+}
+
+/// A sequence of processes that turns an item back into more of itself.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DuplicationLoop {
+    pub steps: Vec<LoopStep>,
+    /// How much of every item is gained (or spent, if negative) per trip,
+    /// when the first step runs once. Only the first step's input and any
+    /// side ingredients end up non-zero.
+    pub net: BTreeMap<Node, f32>,
+}
+
+impl DuplicationLoop {
+    /// The item the loop starts and ends at.
+    pub fn item_ref(&self) -> ItemRef {
+        self.steps[0].input
+    }
+
+    /// How much of the looped item comes back per trip.
+    pub fn gain(&self) -> f32 {
+        self.net
+            .get(&Node::Item(self.item_ref()))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Whether the loop costs nothing else, making it an outright exploit
+    /// rather than a conversion of side ingredients into the looped item.
+    pub fn is_free(&self) -> bool {
+        self.net.values().all(|amount| *amount >= -EPSILON)
+    }
+
+    pub fn involves(&self, item_ref: ItemRef) -> bool {
+        self.steps.iter().any(|step| step.input == item_ref)
+    }
+}
+
+const EPSILON: f32 = 1e-4;
+
+// A process seen as a transformation of one particular input into one
+// particular output.
+#[derive(Clone)]
+struct Conversion {
+    process: ProcessRef,
+    output: ItemRef,
+    consumed: f32,
+    produced: f32,
+}
+
+type Flows = Vec<(Node, f32)>;
+
+// Everything a process consumes and produces, tags included.
+fn process_flows(db: &DB, process: &ProcessRef) -> Result<(Flows, Flows), Error> {
+    let required = |required_items: &[data::RequiredItem]| {
+        required_items
+            .iter()
+            .map(|required_item| {
+                let node = match &required_item.item {
+                    data::ItemRef::Id(id) => Node::Item(db.new_item_ref(id)?),
+                    data::ItemRef::Tag(tag) => Node::Tag(tag.clone()),
+                };
+                Ok((node, required_item.amount as f32))
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    };
+
+    Ok(match process {
+        ProcessRef::Fabricate(fabricate_ref) => {
+            let fabricate = db.get_fabricate(fabricate_ref)?;
+            (
+                required(&fabricate.required_items)?,
+                vec![(Node::Item(fabricate_ref.item_ref), fabricate.amount as f32)],
+            )
+        }
+        ProcessRef::Deconstruct(deconstruct_ref) => {
+            let deconstruct = db.get_deconstruct(deconstruct_ref)?;
+            let mut inputs = vec![(Node::Item(deconstruct_ref.item_ref), 1.0)];
+            inputs.extend(required(&deconstruct.required_items)?);
+            let outputs = deconstruct
+                .items
+                .iter()
+                .map(|produced_item| {
+                    Ok((
+                        Node::Item(db.new_item_ref(&produced_item.id)?),
+                        produced_item.amount as f32,
+                    ))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            (inputs, outputs)
+        }
+    })
+}
+
+fn total(flows: &[(Node, f32)], item_ref: ItemRef) -> f32 {
+    flows
+        .iter()
+        .filter(|(node, _)| *node == Node::Item(item_ref))
+        .map(|(_, amount)| amount)
+        .sum()
+}
+
+struct Search<'a> {
+    db: &'a DB,
+    conversions: BTreeMap<ItemRef, Vec<Conversion>>,
+    path: Vec<(ItemRef, Conversion)>,
+    found: Vec<DuplicationLoop>,
+}
+
+impl<'a> Search<'a> {
+    fn walk(&mut self, start: ItemRef, item_ref: ItemRef, ratio: f32) -> Result<(), Error> {
+        let conversions = self.conversions.get(&item_ref).cloned().unwrap_or_default();
+        for conversion in conversions {
+            let ratio = ratio * conversion.produced / conversion.consumed;
+            if conversion.output == start {
+                if ratio > 1.0 + EPSILON {
+                    self.path.push((item_ref, conversion));
+                    let found = self.close()?;
+                    self.found.push(found);
+                    self.path.pop();
+                }
+                continue;
+            }
+
+            // Only visit items after the start, so each loop is found once,
+            // from its first item.
+            let visited = self
+                .path
+                .iter()
+                .any(|(input, _)| *input == conversion.output);
+            if conversion.output < start
+                || conversion.output == item_ref
+                || visited
+                || self.path.len() + 1 >= MAX_LOOP_STEPS
+            {
+                continue;
+            }
+            let output = conversion.output;
+            self.path.push((item_ref, conversion));
+            self.walk(start, output, ratio)?;
+            self.path.pop();
+        }
+        Ok(())
+    }
+
+    fn close(&self) -> Result<DuplicationLoop, Error> {
+        let mut steps = vec![];
+        let mut net: BTreeMap<Node, f32> = BTreeMap::new();
+        let mut runs = 1.0;
+        for (input, conversion) in &self.path {
+            steps.push(LoopStep {
+                process: conversion.process.clone(),
+                input: *input,
+                output: conversion.output,
+                runs,
+            });
+
+            let (inputs, outputs) = process_flows(self.db, &conversion.process)?;
+            for (node, amount) in inputs {
+                *net.entry(node).or_default() -= amount * runs;
+            }
+            for (node, amount) in outputs {
+                *net.entry(node).or_default() += amount * runs;
+            }
+
+            runs *= conversion.produced / conversion.consumed;
+        }
+        net.retain(|_, amount| amount.abs() > EPSILON);
+        Ok(DuplicationLoop { steps, net })
+    }
+}
+
+/// Find every loop of up to [`MAX_LOOP_STEPS`] processes that ends with more
+/// of an item than it started with.
+pub(crate) fn find_loops(db: &DB) -> Result<Vec<DuplicationLoop>, Error> {
+    let mut conversions: BTreeMap<ItemRef, Vec<Conversion>> = BTreeMap::new();
+    for item_ref in db.item_refs() {
+        let item = db.get_item(item_ref)?;
+        let processes = (0..item.fabricate.len())
+            .map(|idx| ProcessRef::Fabricate(FabricateRef { item_ref, idx }))
+            .chain(
+                (0..item.deconstruct.len())
+                    .map(|idx| ProcessRef::Deconstruct(DeconstructRef { item_ref, idx })),
+            );
+        for process in processes {
+            let (inputs, outputs) = process_flows(db, &process)?;
+            for (input, _) in &inputs {
+                let Node::Item(input) = input else { continue };
+                for (output, _) in &outputs {
+                    let Node::Item(output) = output else { continue };
+                    let conversion = Conversion {
+                        process: process.clone(),
+                        output: *output,
+                        consumed: total(&inputs, *input),
+                        produced: total(&outputs, *output),
+                    };
+                    let existing = conversions.entry(*input).or_default();
+                    if !existing
+                        .iter()
+                        .any(|c| c.process == conversion.process && c.output == conversion.output)
+                    {
+                        existing.push(conversion);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut search = Search {
+        db,
+        conversions,
+        path: vec![],
+        found: vec![],
+    };
+    let starts = search.conversions.keys().copied().collect::<Vec<_>>();
+    for start in starts {
+        search.walk(start, start, 1.0)?;
+    }
+    Ok(search.found)
+}
+
+impl DB {
+    /// Every resource duplication loop in the database. Computed on first
+    /// use, which takes a moment. A database that can't be searched fails
+    /// the same way every time.
+    pub fn duplication_loops(&self) -> Result<&[DuplicationLoop], Error> {
+        self.duplication_loops
+            .get_or_init(|| find_loops(self))
+            .as_deref()
+            .map_err(Clone::clone)
+    }
+
+    /// The duplication loops an item takes part in.
+    pub fn duplication_loops_involving(
+        &self,
+        item_ref: ItemRef,
+    ) -> Result<impl Iterator<Item = &DuplicationLoop>, Error> {
+        Ok(self
+            .duplication_loops()?
+            .iter()
+            .filter(move |found| found.involves(item_ref)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data::Fabricate;
+
+    use crate::test_support::{db, deconstruct, fabricate, item};
+
+    use super::*;
+
+    #[test]
+    fn finds_fabricate_deconstruct_loops() {
+        let mut wire = item("wire");
+        wire.fabricate
+            .push(fabricate(&[("copper", 1), ("tin", 1)], 2));
+        wire.deconstruct.push(deconstruct(&[("copper", 1)]));
+        let db = db(vec![item("copper"), item("tin"), wire]);
+        let copper = db.new_item_ref("copper").unwrap();
+        let tin = db.new_item_ref("tin").unwrap();
+        let wire = db.new_item_ref("wire").unwrap();
+
+        let loops = db.duplication_loops().unwrap();
+        assert_eq!(loops.len(), 1);
+        let found = &loops[0];
+        assert_eq!(found.item_ref(), copper);
+        // One copper and one tin make two wires, which deconstruct into two
+        // copper.
+        assert_eq!(found.steps[1].runs, 2.0);
+        assert_eq!(found.gain(), 1.0);
+        assert_eq!(found.net[&Node::Item(tin)], -1.0);
+        assert!(!found.is_free());
+        assert!(found.involves(wire));
+        assert_eq!(db.duplication_loops_involving(tin).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn ignores_lossy_loops() {
+        let mut wire = item("wire");
+        wire.fabricate.push(fabricate(&[("copper", 2)], 1));
+        wire.deconstruct.push(deconstruct(&[("copper", 1)]));
+        let db = db(vec![item("copper"), wire]);
+        assert!(db.duplication_loops().unwrap().is_empty());
+    }
+
+    #[test]
+    fn finds_longer_loops() {
+        let mut a = item("a");
+        a.deconstruct.push(deconstruct(&[("b", 1)]));
+        let mut b = item("b");
+        b.deconstruct.push(deconstruct(&[("c", 3)]));
+        let mut c = item("c");
+        c.deconstruct.push(deconstruct(&[("a", 1)]));
+        let db = db(vec![a, b, c]);
+
+        let loops = db.duplication_loops().unwrap();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].steps.len(), 3);
+        assert_eq!(loops[0].gain(), 2.0);
+        assert!(loops[0].is_free());
+    }
+
+    #[test]
+    fn follows_recycling_forwards() {
+        // Recycling a wire gives two copper, like any other recipe for copper.
+        let mut copper = item("copper");
+        copper.fabricate.push(Fabricate {
+            recycle: true,
+            ..fabricate(&[("wire", 1)], 2)
+        });
+        let mut wire = item("wire");
+        wire.fabricate.push(fabricate(&[("copper", 1)], 1));
+        let db = db(vec![copper, wire]);
+
+        let loops = db.duplication_loops().unwrap();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].gain(), 1.0);
+    }
+}
@@ -0,0 +1,145 @@
+use barohead_data::items::Provenance;
+
+use crate::{Error, ItemRef, DB};
+
+/// Narrows items down by the content package they came from.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub enum ModFilter {
+    #[default]
+    All,
+    /// Only items a package defined or overrode, or that are variants of
+    /// those.
+    Only(String),
+    /// Everything except items a package defined or overrode, and variants
+    /// of those. Earlier definitions of items it overrode aren't kept, so
+    /// those go too.
+    Hide(String),
+}
+
+impl ModFilter {
+    pub fn matches(&self, provenance: Option<&Provenance>) -> bool {
+        let from = |name: &String| {
+            provenance
+                .is_some_and(|provenance| provenance.packages().any(|package| package == name))
+        };
+        match self {
+            ModFilter::All => true,
+            ModFilter::Only(name) => from(name),
+            ModFilter::Hide(name) => !from(name),
+        }
+    }
+}
+
+impl DB {
+    /// Content package names in load order, the base game first. Empty for
+    /// databases built without package information.
+    pub fn packages(&self) -> &[String] {
+        &self.packages
+    }
+
+    pub fn provenance(&self, item_ref: ItemRef) -> Result<Option<&Provenance>, Error> {
+        Ok(self.get_item(item_ref)?.provenance.as_ref())
+    }
+
+    /// Whether an item comes from something other than the base game,
+    /// either as a new item, by overriding one, or as a variant of a modded
+    /// one.
+    pub fn is_modded(&self, item_ref: ItemRef) -> Result<bool, Error> {
+        let base = self.packages.first();
+        Ok(self
+            .provenance(item_ref)?
+            .is_some_and(|provenance| provenance.packages().any(|package| Some(package) != base)))
+    }
+
+    pub fn filter_items(&self, filter: &ModFilter) -> Vec<ItemRef> {
+        self.item_refs()
+            .filter(|item_ref| {
+                self.provenance(*item_ref)
+                    .is_ok_and(|provenance| filter.matches(provenance))
+            })
+            .collect()
+    }
+
+    /// Items defined by more than one package, where only the last one
+    /// counts.
+    pub fn overridden_items(&self) -> Vec<ItemRef> {
+        self.item_refs()
+            .filter(|item_ref| {
+                self.provenance(*item_ref).is_ok_and(|provenance| {
+                    provenance.is_some_and(|provenance| !provenance.overrides.is_empty())
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use barohead_data::items::Item;
+
+    use crate::test_support::{self, itemdb};
+
+    use super::*;
+
+    fn item(id: &str, package: Option<&str>, overrides: &[&str]) -> Item {
+        Item {
+            provenance: package.map(|package| Provenance {
+                package: package.to_owned(),
+                overrides: overrides.iter().map(|name| name.to_string()).collect(),
+                inherits: vec![],
+            }),
+            ..test_support::item(id)
+        }
+    }
+
+    #[test]
+    fn filters_by_package() {
+        // The base game's variant, built on the modded wire.
+        let mut shinywire = item("shinywire", Some("Vanilla"), &[]);
+        shinywire.provenance.as_mut().unwrap().inherits = vec!["Better Wire".to_owned()];
+        let mut itemdb = itemdb(
+            vec![
+                item("wire", Some("Better Wire"), &["Vanilla", "Wire Tweaks"]),
+                item("fuse", Some("Vanilla"), &[]),
+                item("railgun", Some("Big Guns"), &[]),
+                item("mystery", None, &[]),
+                shinywire,
+            ],
+            &[],
+        );
+        itemdb.packages = ["Vanilla", "Wire Tweaks", "Better Wire", "Big Guns"]
+            .map(str::to_owned)
+            .to_vec();
+        let db = DB::new(itemdb).unwrap();
+        let ids = |item_refs: Vec<ItemRef>| {
+            item_refs
+                .into_iter()
+                .map(|item_ref| db.get_item(item_ref).unwrap().id.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(db.filter_items(&ModFilter::All)).len(), 5);
+        assert_eq!(
+            ids(db.filter_items(&ModFilter::Only("Big Guns".to_owned()))),
+            vec!["railgun"]
+        );
+        assert_eq!(
+            ids(db.filter_items(&ModFilter::Only("Better Wire".to_owned()))),
+            vec!["wire", "shinywire"]
+        );
+        assert_eq!(
+            ids(db.filter_items(&ModFilter::Hide("Better Wire".to_owned()))),
+            vec!["fuse", "railgun", "mystery"]
+        );
+        assert_eq!(ids(db.overridden_items()), vec!["wire"]);
+
+        let railgun = db.new_item_ref("railgun").unwrap();
+        let fuse = db.new_item_ref("fuse").unwrap();
+        let mystery = db.new_item_ref("mystery").unwrap();
+        assert!(db.is_modded(railgun).unwrap());
+        assert!(!db.is_modded(fuse).unwrap());
+        assert!(!db.is_modded(mystery).unwrap());
+        let shinywire = db.new_item_ref("shinywire").unwrap();
+        assert!(db.is_modded(shinywire).unwrap());
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use barohead_data::items::{self as data, Fabricator, StoreIdentifier};
+
+use crate::bom::chosen_recipe;
+use crate::{BillOfMaterials, Campaign, Error, ItemRef, Material, StoreSummary, DB};
+
+/// Something to buy, and what the store charges for each.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Purchase {
+    pub item_ref: ItemRef,
+    pub amount: i32,
+    pub unit_price: i32,
+}
+
+/// How to get a set of items together by shopping at one store: buy what's
+/// cheaper to buy, make the rest, and find whatever the store doesn't sell.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProductionPlan {
+    pub store: StoreIdentifier,
+    pub campaign: Campaign,
+    /// Everything that gets made, bottoming out at what's bought or found.
+    pub bom: BillOfMaterials,
+    pub purchases: Vec<Purchase>,
+    /// Materials the store doesn't sell.
+    pub unavailable: BTreeMap<Material, i32>,
+    /// Seconds of fabrication, by the kind of fabricator each recipe uses.
+    pub fabricator_time: BTreeMap<Fabricator, f32>,
+}
+
+impl ProductionPlan {
+    pub fn cost(&self) -> i32 {
+        self.purchases
+            .iter()
+            .map(|purchase| purchase.amount * purchase.unit_price)
+            .sum()
+    }
+}
+
+struct Costing<'a> {
+    db: &'a DB,
+    store: StoreIdentifier,
+    campaign: Campaign,
+    costs: BTreeMap<ItemRef, Option<f32>>,
+    stack: BTreeSet<ItemRef>,
+    bought: BTreeSet<ItemRef>,
+}
+
+impl<'a> Costing<'a> {
+    fn store_price(&self, item_ref: ItemRef) -> Result<Option<i32>, Error> {
+        Ok(self
+            .db
+            .get_item(item_ref)?
+            .price
+            .as_ref()
+            .and_then(|price| StoreSummary::for_campaign(price, self.store, &self.campaign).sell))
+    }
+
+    // The cheapest way to get one of an item, deciding along the way whether
+    // to buy it. Tags can't be priced, so anything with a tag ingredient
+    // counts as having no known cost to make.
+    fn unit_cost(&mut self, item_ref: ItemRef) -> Result<Option<f32>, Error> {
+        if let Some(cost) = self.costs.get(&item_ref) {
+            return Ok(*cost);
+        }
+        if !self.stack.insert(item_ref) {
+            return Ok(None);
+        }
+
+        let item = self.db.get_item(item_ref)?;
+        let mut craft_cost = None;
+        if let Some(idx) = chosen_recipe(item) {
+            let fabricate = &item.fabricate[idx];
+            let mut total = Some(0.0);
+            for required_item in &fabricate.required_items {
+                let cost = match &required_item.item {
+                    data::ItemRef::Id(id) => self.unit_cost(self.db.new_item_ref(id)?)?,
+                    data::ItemRef::Tag(_) => None,
+                };
+                total = total
+                    .zip(cost)
+                    .map(|(total, cost)| total + cost * required_item.amount as f32);
+            }
+            craft_cost = total.map(|total| total / fabricate.amount.max(1) as f32);
+        }
+        self.stack.remove(&item_ref);
+
+        let buy_cost = self.store_price(item_ref)?.map(|price| price as f32);
+        let cost = match (buy_cost, craft_cost) {
+            (Some(buy), Some(craft)) if craft < buy => Some(craft),
+            (Some(buy), _) => {
+                self.bought.insert(item_ref);
+                Some(buy)
+            }
+            (None, craft) => craft,
+        };
+        self.costs.insert(item_ref, cost);
+        Ok(cost)
+    }
+}
+
+impl DB {
+    /// Plan getting hold of the targets, shopping only at `store` at the
+    /// prices it charges in `campaign`.
+    pub fn production_plan(
+        &self,
+        targets: &[(ItemRef, i32)],
+        store: StoreIdentifier,
+        campaign: &Campaign,
+    ) -> Result<ProductionPlan, Error> {
+        let mut costing = Costing {
+            db: self,
+            store,
+            campaign: *campaign,
+            costs: BTreeMap::new(),
+            stack: BTreeSet::new(),
+            bought: BTreeSet::new(),
+        };
+        for (item_ref, _) in targets {
+            costing.unit_cost(*item_ref)?;
+        }
+
+        let bom = self.bill_of_materials_buying(targets, &costing.bought)?;
+
+        let mut purchases = vec![];
+        let mut unavailable = BTreeMap::new();
+        for (material, amount) in &bom.materials {
+            let unit_price = match material {
+                Material::Item(item_ref) => costing.store_price(*item_ref)?,
+                Material::Tag(_) => None,
+            };
+            match (material, unit_price) {
+                (Material::Item(item_ref), Some(unit_price)) => purchases.push(Purchase {
+                    item_ref: *item_ref,
+                    amount: *amount,
+                    unit_price,
+                }),
+                _ => {
+                    unavailable.insert(material.clone(), *amount);
+                }
+            }
+        }
+
+        let mut fabricator_time: BTreeMap<Fabricator, f32> = BTreeMap::new();
+        for step in &bom.steps {
+            let fabricate = self.get_fabricate(&step.fabricate_ref)?;
+            let fabricator = fabricate
+                .suitable_fabricators
+                .first()
+                .cloned()
+                .unwrap_or(Fabricator::Fabricator);
+            *fabricator_time.entry(fabricator).or_default() += fabricate.time * step.runs as f32;
+        }
+
+        Ok(ProductionPlan {
+            store,
+            campaign: *campaign,
+            bom,
+            purchases,
+            unavailable,
+            fabricator_time,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data::{Item, Price};
+
+    use crate::test_support::{self, db, fabricate};
+
+    use super::*;
+
+    fn item(id: &str, recipe: &[(&str, i32)], price: Option<i32>) -> Item {
+        let mut item = test_support::item(id);
+        if !recipe.is_empty() {
+            item.fabricate.push(data::Fabricate {
+                time: 10.0,
+                ..fabricate(recipe, 1)
+            });
+        }
+        item.price = price.map(|baseprice| Price {
+            baseprice,
+            ..Default::default()
+        });
+        item
+    }
+
+    #[test]
+    fn buys_whatever_is_cheaper() {
+        let db = db(vec![
+            item("copper", &[], Some(10)),
+            item("ore", &[], None),
+            // Cheaper to make than to buy.
+            item("wire", &[("copper", 1)], Some(50)),
+            // Cheaper to buy than to make.
+            item("plate", &[("copper", 5)], Some(20)),
+            item("gadget", &[("wire", 2), ("plate", 1), ("ore", 1)], None),
+        ]);
+        let [copper, ore, wire, plate, gadget] =
+            ["copper", "ore", "wire", "plate", "gadget"].map(|id| db.new_item_ref(id).unwrap());
+
+        let plan = db
+            .production_plan(
+                &[(gadget, 2)],
+                StoreIdentifier::MerchantCity,
+                &Campaign::default(),
+            )
+            .unwrap();
+
+        let made = plan
+            .bom
+            .steps
+            .iter()
+            .map(|step| (step.fabricate_ref.item_ref, step.runs))
+            .collect::<Vec<_>>();
+        assert_eq!(made, vec![(wire, 4), (gadget, 2)]);
+        assert_eq!(
+            plan.purchases,
+            vec![
+                Purchase {
+                    item_ref: copper,
+                    amount: 4,
+                    unit_price: 10
+                },
+                Purchase {
+                    item_ref: plate,
+                    amount: 2,
+                    unit_price: 20
+                },
+            ]
+        );
+        assert_eq!(plan.cost(), 80);
+        assert_eq!(
+            plan.unavailable,
+            [(Material::Item(ore), 2)].into_iter().collect()
+        );
+        assert_eq!(plan.fabricator_time[&Fabricator::Fabricator], 60.0);
+    }
+}
@@ -0,0 +1,435 @@
+use barohead_data::items::{Price, StoreIdentifier};
+
+/// The merchants that show up in a normal campaign.
+pub static INTERESTING_MERCHANTS: [StoreIdentifier; 10] = [
+    StoreIdentifier::MerchantOutpost,
+    StoreIdentifier::MerchantCity,
+    StoreIdentifier::MerchantResearch,
+    StoreIdentifier::MerchantMilitary,
+    StoreIdentifier::MerchantMine,
+    StoreIdentifier::MerchantMedical,
+    StoreIdentifier::MerchantEngineering,
+    StoreIdentifier::MerchantArmory,
+    StoreIdentifier::MerchantClown,
+    StoreIdentifier::MerchantHusk,
+];
+
+/// Merchants pay this much of an item's price when buying it back.
+const STORE_SELL_PRICE_MODIFIER: f32 = 0.3;
+
+/// How far the best or worst reputation moves prices.
+const MAX_REPUTATION_MODIFIER: f32 = 0.1;
+
+/// The parts of a campaign that change what merchants charge and pay.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Campaign {
+    /// Reputation with the faction that runs the location, or `None` where no
+    /// faction does.
+    pub faction_reputation: Option<f32>,
+    /// Reputation with the location itself, which only counts where no
+    /// faction runs it.
+    pub location_reputation: f32,
+    /// The campaign's store price setting, which applies to what merchants
+    /// charge but not to what they pay.
+    pub store_price_multiplier: f32,
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        Self {
+            faction_reputation: None,
+            location_reputation: 0.0,
+            store_price_multiplier: 1.0,
+        }
+    }
+}
+
+impl Campaign {
+    pub const MIN_REPUTATION: f32 = -100.0;
+    pub const MAX_REPUTATION: f32 = 100.0;
+
+    /// The reputation merchants go by: the faction's where there is one,
+    /// otherwise the location's.
+    pub fn reputation(&self) -> f32 {
+        self.faction_reputation
+            .unwrap_or(self.location_reputation)
+            .clamp(Self::MIN_REPUTATION, Self::MAX_REPUTATION)
+    }
+
+    // Good reputation makes merchants charge less and pay more, and bad
+    // reputation the opposite, scaling linearly up to the limits.
+    fn reputation_modifier(&self, charging: bool) -> f32 {
+        let reputation = self.reputation();
+        let change = if reputation >= 0.0 {
+            -MAX_REPUTATION_MODIFIER * reputation / Self::MAX_REPUTATION
+        } else {
+            MAX_REPUTATION_MODIFIER * reputation / Self::MIN_REPUTATION
+        };
+        if charging {
+            1.0 + change
+        } else {
+            1.0 - change
+        }
+    }
+}
+
+/// How many of an item a store has after restocking. The game picks an amount
+/// in this range, both ends included.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Stock {
+    pub min: i32,
+    pub max: i32,
+}
+
+impl Stock {
+    fn new(min: Option<i32>, max: Option<i32>) -> Option<Self> {
+        if min.is_none() && max.is_none() {
+            return None;
+        }
+        let min = min.unwrap_or(0);
+        Some(Stock {
+            min,
+            max: max.unwrap_or(min).max(min),
+        })
+    }
+}
+
+/// What a single store charges for an item, and what it pays for one.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct StoreSummary {
+    /// The price the store sells at, if it sells the item at all.
+    pub sell: Option<i32>,
+    /// The price the store buys at.
+    pub buy: i32,
+    /// How many the store keeps, for items it sells and whose data says.
+    pub stock: Option<Stock>,
+    /// The store only stocks the item at locations at least this difficult.
+    pub min_level_difficulty: i32,
+    /// The store only stocks the item once it has been unlocked.
+    pub requires_unlock: bool,
+    /// Whether the item can be the store's daily special or in demand there.
+    pub can_be_special: bool,
+}
+
+impl StoreSummary {
+    /// Prices with neutral reputation and default campaign settings.
+    pub fn for_store(price: &Price, store: StoreIdentifier) -> Self {
+        Self::for_campaign(price, store, &Campaign::default())
+    }
+
+    /// Prices as the game works them out partway through a campaign.
+    pub fn for_campaign(price: &Price, store: StoreIdentifier, campaign: &Campaign) -> Self {
+        Self::for_condition(price, store, campaign, 1.0)
+    }
+
+    /// Prices for selling a damaged item, with `condition` the fraction of
+    /// its condition left. Merchants pay less for it in proportion, while
+    /// what they charge is still for a new one.
+    pub fn for_condition(
+        price: &Price,
+        store: StoreIdentifier,
+        campaign: &Campaign,
+        condition: f32,
+    ) -> Self {
+        let matching_modifier = price.modifiers.get(&store);
+
+        let sold = if Self::is_specialist_merchant(store) {
+            matching_modifier
+                .map(|m| m.sold.unwrap_or(price.sold))
+                .unwrap_or(false)
+        } else {
+            matching_modifier.and_then(|m| m.sold).unwrap_or(price.sold)
+        };
+
+        let multiplier = matching_modifier.and_then(|m| m.multiplier);
+
+        let store_price: f32 = multiplier
+            .map(|mul| mul * price.baseprice as f32)
+            .unwrap_or(price.baseprice as f32);
+
+        let sell_price =
+            store_price * campaign.reputation_modifier(true) * campaign.store_price_multiplier;
+        let buy_price = store_price
+            * STORE_SELL_PRICE_MODIFIER
+            * campaign.reputation_modifier(false)
+            * condition.clamp(0.0, 1.0);
+
+        let stock = Stock::new(
+            matching_modifier
+                .and_then(|m| m.min_available)
+                .or(price.min_available),
+            matching_modifier
+                .and_then(|m| m.max_available)
+                .or(price.max_available),
+        );
+
+        Self {
+            // The game rounds to the nearest mark, not down.
+            buy: buy_price.round() as i32,
+            sell: sold.then_some(sell_price.round() as i32),
+            stock: stock.filter(|_| sold),
+            min_level_difficulty: matching_modifier
+                .and_then(|m| m.min_level_difficulty)
+                .or(price.min_level_difficulty)
+                .unwrap_or(0),
+            requires_unlock: matching_modifier
+                .and_then(|m| m.requires_unlock)
+                .unwrap_or(price.requires_unlock),
+            can_be_special: matching_modifier
+                .and_then(|m| m.can_be_special)
+                .unwrap_or(price.can_be_special),
+        }
+    }
+
+    /// Specialist merchants only sell what they're explicitly told to.
+    pub fn is_specialist_merchant(store: StoreIdentifier) -> bool {
+        matches!(
+            store,
+            StoreIdentifier::MerchantMedical
+                | StoreIdentifier::MerchantEngineering
+                | StoreIdentifier::MerchantArmory
+                | StoreIdentifier::MerchantClown
+                | StoreIdentifier::MerchantHusk
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use barohead_data::items::PriceModifier;
+
+    use super::*;
+
+    fn price(sold: bool, modifiers: Vec<(StoreIdentifier, PriceModifier)>) -> Price {
+        Price {
+            baseprice: 100,
+            sold,
+            modifiers: modifiers.into_iter().collect::<BTreeMap<_, _>>(),
+            ..Default::default()
+        }
+    }
+
+    fn modifier(multiplier: Option<f32>, sold: Option<bool>) -> PriceModifier {
+        PriceModifier {
+            multiplier,
+            sold,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn general_merchants_use_the_base_price() {
+        let summary = StoreSummary::for_store(&price(true, vec![]), StoreIdentifier::MerchantCity);
+        assert_eq!(
+            summary,
+            StoreSummary {
+                sell: Some(100),
+                buy: 30,
+                stock: None,
+                min_level_difficulty: 0,
+                requires_unlock: false,
+                can_be_special: true,
+            }
+        );
+    }
+
+    #[test]
+    fn modifiers_apply_to_both_directions() {
+        let modifier = modifier(Some(1.5), None);
+        let price = price(true, vec![(StoreIdentifier::MerchantMine, modifier)]);
+        let summary = StoreSummary::for_store(&price, StoreIdentifier::MerchantMine);
+        assert_eq!(
+            summary,
+            StoreSummary {
+                sell: Some(150),
+                buy: 45,
+                stock: None,
+                min_level_difficulty: 0,
+                requires_unlock: false,
+                can_be_special: true,
+            }
+        );
+    }
+
+    #[test]
+    fn specialist_merchants_need_a_modifier() {
+        let modifier = modifier(None, None);
+        let price = price(true, vec![(StoreIdentifier::MerchantMedical, modifier)]);
+        assert_eq!(
+            StoreSummary::for_store(&price, StoreIdentifier::MerchantMedical).sell,
+            Some(100)
+        );
+        assert_eq!(
+            StoreSummary::for_store(&price, StoreIdentifier::MerchantArmory).sell,
+            None
+        );
+    }
+
+    #[test]
+    fn modifiers_can_stop_sales() {
+        let modifier = modifier(None, Some(false));
+        let price = price(true, vec![(StoreIdentifier::MerchantOutpost, modifier)]);
+        assert_eq!(
+            StoreSummary::for_store(&price, StoreIdentifier::MerchantOutpost).sell,
+            None
+        );
+        assert_eq!(
+            StoreSummary::for_store(&price, StoreIdentifier::MerchantCity).sell,
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn stores_can_change_the_stock() {
+        let mut price = price(
+            true,
+            vec![
+                (
+                    StoreIdentifier::MerchantOutpost,
+                    PriceModifier {
+                        min_available: Some(1),
+                        max_available: Some(3),
+                        min_level_difficulty: Some(20),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    StoreIdentifier::MerchantMine,
+                    PriceModifier {
+                        max_available: Some(2),
+                        ..Default::default()
+                    },
+                ),
+                (StoreIdentifier::MerchantCity, modifier(None, Some(false))),
+            ],
+        );
+        price.min_available = Some(5);
+        price.min_level_difficulty = Some(10);
+
+        let outpost = StoreSummary::for_store(&price, StoreIdentifier::MerchantOutpost);
+        assert_eq!(outpost.stock, Some(Stock { min: 1, max: 3 }));
+        assert_eq!(outpost.min_level_difficulty, 20);
+        // A maximum below the inherited minimum doesn't make an empty range.
+        let mine = StoreSummary::for_store(&price, StoreIdentifier::MerchantMine);
+        assert_eq!(mine.stock, Some(Stock { min: 5, max: 5 }));
+        let research = StoreSummary::for_store(&price, StoreIdentifier::MerchantResearch);
+        assert_eq!(research.stock, Some(Stock { min: 5, max: 5 }));
+        assert_eq!(research.min_level_difficulty, 10);
+        // Nothing is stocked where it isn't sold.
+        let city = StoreSummary::for_store(&price, StoreIdentifier::MerchantCity);
+        assert_eq!(city.stock, None);
+    }
+
+    #[test]
+    fn stores_can_change_the_flags() {
+        let mut price = price(
+            true,
+            vec![(
+                StoreIdentifier::MerchantOutpost,
+                PriceModifier {
+                    requires_unlock: Some(false),
+                    can_be_special: Some(true),
+                    ..Default::default()
+                },
+            )],
+        );
+        price.requires_unlock = true;
+        price.can_be_special = false;
+
+        let outpost = StoreSummary::for_store(&price, StoreIdentifier::MerchantOutpost);
+        assert!(!outpost.requires_unlock && outpost.can_be_special);
+        let city = StoreSummary::for_store(&price, StoreIdentifier::MerchantCity);
+        assert!(city.requires_unlock && !city.can_be_special);
+    }
+
+    #[test]
+    fn campaigns_change_prices() {
+        let price = price(true, vec![]);
+        let at = |location_reputation, store_price_multiplier| {
+            let campaign = Campaign {
+                location_reputation,
+                store_price_multiplier,
+                ..Default::default()
+            };
+            let summary =
+                StoreSummary::for_campaign(&price, StoreIdentifier::MerchantCity, &campaign);
+            (summary.sell.unwrap(), summary.buy)
+        };
+
+        assert_eq!(at(0.0, 1.0), (100, 30));
+        assert_eq!(at(100.0, 1.0), (90, 33));
+        assert_eq!(at(-25.0, 1.0), (103, 29));
+        // Out of range reputation counts as the limit.
+        assert_eq!(at(-500.0, 1.0), (110, 27));
+        // The campaign setting only changes what merchants charge.
+        assert_eq!(at(0.0, 2.0), (200, 30));
+    }
+
+    #[test]
+    fn faction_reputation_wins() {
+        let price = price(true, vec![]);
+        let at = |campaign: Campaign| {
+            let summary =
+                StoreSummary::for_campaign(&price, StoreIdentifier::MerchantCity, &campaign);
+            (summary.sell.unwrap(), summary.buy)
+        };
+        let outpost = Campaign {
+            location_reputation: -100.0,
+            ..Default::default()
+        };
+
+        assert_eq!(at(outpost), (110, 27));
+        assert_eq!(
+            at(Campaign {
+                faction_reputation: Some(100.0),
+                ..outpost
+            }),
+            (90, 33)
+        );
+    }
+
+    #[test]
+    fn prices_round_to_the_nearest_mark() {
+        let mut price = price(true, vec![]);
+        price.baseprice = 5;
+        let campaign = Campaign {
+            store_price_multiplier: 0.5,
+            ..Default::default()
+        };
+        let at = |condition| {
+            let summary = StoreSummary::for_condition(
+                &price,
+                StoreIdentifier::MerchantCity,
+                &campaign,
+                condition,
+            );
+            (summary.sell.unwrap(), summary.buy)
+        };
+
+        // Charging 2.5 and paying 1.5, or 0.75 for a half-broken one.
+        assert_eq!(at(1.0), (3, 2));
+        assert_eq!(at(0.5), (3, 1));
+    }
+
+    #[test]
+    fn damaged_items_sell_for_less() {
+        let price = price(true, vec![]);
+        let at = |condition| {
+            let summary = StoreSummary::for_condition(
+                &price,
+                StoreIdentifier::MerchantCity,
+                &Campaign::default(),
+                condition,
+            );
+            (summary.sell.unwrap(), summary.buy)
+        };
+
+        assert_eq!(at(1.0), (100, 30));
+        assert_eq!(at(0.5), (100, 15));
+        assert_eq!(at(0.0), (100, 0));
+        // Condition can't go past its maximum.
+        assert_eq!(at(1.5), (100, 30));
+    }
+}
@@ -0,0 +1,159 @@
+use barohead_data::items::StoreIdentifier;
+
+use crate::{Campaign, Error, ItemRef, StoreSummary, DB};
+
+/// What a merchant pays for an item as it is, against what they pay for
+/// everything deconstructing it gives.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SalvageValue {
+    pub sell: i32,
+    pub deconstruct: i32,
+    /// Outputs without a price, which no merchant will take.
+    pub unpriced_outputs: usize,
+}
+
+impl SalvageValue {
+    /// How much more deconstructing gets, negative when selling is better.
+    pub fn gain(&self) -> i32 {
+        self.deconstruct - self.sell
+    }
+}
+
+impl DB {
+    /// Whether an item is worth more to a merchant whole or deconstructed,
+    /// for an item with `condition` of its condition left. Outputs are valued
+    /// as new, and only count if an item that worn gives them. Items with
+    /// several ways to deconstruct them use the most valuable.
+    ///
+    /// Nothing to compare unless the item has both a price and a way to
+    /// deconstruct it.
+    pub fn salvage_value(
+        &self,
+        item_ref: ItemRef,
+        store: StoreIdentifier,
+        campaign: &Campaign,
+        condition: f32,
+    ) -> Result<Option<SalvageValue>, Error> {
+        let item = self.get_item(item_ref)?;
+        let Some(price) = &item.price else {
+            return Ok(None);
+        };
+        let sell = StoreSummary::for_condition(price, store, campaign, condition).buy;
+
+        let mut best: Option<SalvageValue> = None;
+        for deconstruct in &item.deconstruct {
+            let mut value = SalvageValue {
+                sell,
+                deconstruct: 0,
+                unpriced_outputs: 0,
+            };
+            for produced_item in &deconstruct.items {
+                if !produced_item.is_produced_at(condition) {
+                    continue;
+                }
+                let output = self.get_item(self.new_item_ref(&produced_item.id)?)?;
+                match &output.price {
+                    Some(price) => {
+                        value.deconstruct += StoreSummary::for_campaign(price, store, campaign).buy
+                            * produced_item.amount;
+                    }
+                    None => value.unpriced_outputs += 1,
+                }
+            }
+            if best.map_or(true, |best| value.deconstruct > best.deconstruct) {
+                best = Some(value);
+            }
+        }
+        Ok(best)
+    }
+
+    /// Items with both a price and a way to deconstruct them.
+    pub fn salvageable_items(&self) -> Vec<ItemRef> {
+        self.item_refs()
+            .filter(|item_ref| {
+                let item = self.get_item(*item_ref).unwrap();
+                item.price.is_some() && !item.deconstruct.is_empty()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use barohead_data::items::{Item, Price, ProducedItem};
+
+    use crate::test_support::{self, db, deconstruct, produced};
+
+    use super::*;
+
+    fn item(id: &str, baseprice: Option<i32>) -> Item {
+        Item {
+            price: baseprice.map(|baseprice| Price {
+                baseprice,
+                ..Default::default()
+            }),
+            ..test_support::item(id)
+        }
+    }
+
+    #[test]
+    fn compares_selling_with_deconstructing() {
+        let mut gadget = item("gadget", Some(100));
+        let mut recipe = deconstruct(&[("copper", 2), ("junk", 1)]);
+        recipe.items.insert(
+            1,
+            ProducedItem {
+                mincondition: Some(0.5),
+                ..produced("chip", 1)
+            },
+        );
+        gadget.deconstruct.push(recipe);
+
+        let db = db(vec![
+            gadget,
+            item("copper", Some(40)),
+            item("chip", Some(100)),
+            item("junk", None),
+        ]);
+        let [gadget, copper] = ["gadget", "copper"].map(|id| db.new_item_ref(id).unwrap());
+        let at = |condition| {
+            db.salvage_value(
+                gadget,
+                StoreIdentifier::MerchantCity,
+                &Campaign::default(),
+                condition,
+            )
+            .unwrap()
+            .unwrap()
+        };
+
+        assert_eq!(db.salvageable_items(), vec![gadget]);
+        assert_eq!(
+            at(1.0),
+            SalvageValue {
+                sell: 30,
+                deconstruct: 2 * 12 + 30,
+                unpriced_outputs: 1,
+            }
+        );
+        assert_eq!(at(1.0).gain(), 24);
+        // Too worn to give the chip, but worth less whole too.
+        assert_eq!(
+            at(0.25),
+            SalvageValue {
+                sell: 8,
+                deconstruct: 2 * 12,
+                unpriced_outputs: 1,
+            }
+        );
+        assert_eq!(
+            db.salvage_value(
+                copper,
+                StoreIdentifier::MerchantCity,
+                &Campaign::default(),
+                1.0
+            ),
+            Ok(None)
+        );
+    }
+}
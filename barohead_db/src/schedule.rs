@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+
+use barohead_data::items::{self as data, Fabricator, Skill};
+
+use crate::{BillOfMaterials, Error, ItemRef, DB};
+
+/// How long one run of a recipe takes someone with the given skill levels,
+/// following the game: meeting the requirements exactly takes the listed
+/// time, exceeding them is up to twice as fast, and falling short gets
+/// drastically slower. Recipes without requirements count as a full success,
+/// so they take half the listed time.
+pub fn fabrication_time(fabricate: &data::Fabricate, skills: &BTreeMap<Skill, i32>) -> f32 {
+    let degree_of_success = if fabricate.required_skills.is_empty() {
+        1.0
+    } else {
+        let surplus = fabricate
+            .required_skills
+            .iter()
+            .map(|(skill, level)| (skills.get(skill).copied().unwrap_or(0) - level) as f32)
+            .sum::<f32>()
+            / fabricate.required_skills.len() as f32;
+        (surplus + 100.0) / 200.0
+    };
+    let speed = if degree_of_success < 0.5 {
+        degree_of_success * degree_of_success
+    } else {
+        degree_of_success * 2.0
+    };
+    fabricate.time / speed.clamp(0.01, 2.0)
+}
+
+/// One run of a bill of materials step on a particular machine.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ScheduledRun {
+    /// Index into [`BillOfMaterials::steps`].
+    pub step: usize,
+    pub fabricator: Fabricator,
+    /// Which of the fabricators of that kind, counting from 0.
+    pub machine: usize,
+    pub start: f32,
+    pub end: f32,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Schedule {
+    pub runs: Vec<ScheduledRun>,
+    /// Steps that can't happen, because there's no suitable fabricator aboard
+    /// for them or something they need.
+    pub unscheduled: Vec<usize>,
+}
+
+impl Schedule {
+    /// Wall-clock seconds until the last run finishes.
+    pub fn duration(&self) -> f32 {
+        self.runs.iter().map(|run| run.end).fold(0.0, f32::max)
+    }
+}
+
+impl DB {
+    /// Fit every run of a bill of materials onto the fabricators aboard. Each
+    /// run starts as soon as a suitable machine is free and the items it uses
+    /// have been made, earliest made first. Everything bought or found is to
+    /// hand from the start.
+    pub fn schedule(
+        &self,
+        bom: &BillOfMaterials,
+        machines: &BTreeMap<Fabricator, usize>,
+        skills: &BTreeMap<Skill, i32>,
+    ) -> Result<Schedule, Error> {
+        let mut free_at: BTreeMap<Fabricator, Vec<f32>> = machines
+            .iter()
+            .map(|(fabricator, count)| (fabricator.clone(), vec![0.0; *count]))
+            .collect();
+        // How many of each made item have been used so far, and when each one
+        // becomes available, with `None` for ones that never will.
+        let mut made: BTreeMap<ItemRef, (usize, Vec<Option<f32>>)> = BTreeMap::new();
+        let mut schedule = Schedule::default();
+
+        for (idx, step) in bom.steps.iter().enumerate() {
+            let fabricate = self.get_fabricate(&step.fabricate_ref)?;
+            let time = fabrication_time(fabricate, skills);
+            let mut suitable = fabricate.suitable_fabricators.clone();
+            if suitable.is_empty() {
+                suitable.push(Fabricator::Fabricator);
+            }
+
+            let mut outputs = vec![];
+            for _ in 0..step.runs {
+                let mut ready = Some(0.0_f32);
+                for required_item in &fabricate.required_items {
+                    let data::ItemRef::Id(id) = &required_item.item else {
+                        continue;
+                    };
+                    let Some((next, available)) = made.get_mut(&self.new_item_ref(id)?) else {
+                        continue;
+                    };
+                    for _ in 0..required_item.amount {
+                        let at = available.get(*next).copied().flatten();
+                        *next += 1;
+                        ready = ready.zip(at).map(|(ready, at)| ready.max(at));
+                    }
+                }
+
+                let machine = suitable
+                    .iter()
+                    .flat_map(|fabricator| {
+                        free_at
+                            .get(fabricator)
+                            .into_iter()
+                            .flatten()
+                            .enumerate()
+                            .map(move |(machine, free)| (*free, fabricator, machine))
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+
+                let end = match (ready, machine) {
+                    (Some(ready), Some((free, fabricator, machine))) => {
+                        let start = ready.max(free);
+                        let end = start + time;
+                        free_at.get_mut(fabricator).unwrap()[machine] = end;
+                        schedule.runs.push(ScheduledRun {
+                            step: idx,
+                            fabricator: fabricator.clone(),
+                            machine,
+                            start,
+                            end,
+                        });
+                        Some(end)
+                    }
+                    _ => {
+                        if schedule.unscheduled.last() != Some(&idx) {
+                            schedule.unscheduled.push(idx);
+                        }
+                        None
+                    }
+                };
+                outputs.extend(std::iter::repeat(end).take(fabricate.amount.max(1) as usize));
+            }
+            made.insert(step.fabricate_ref.item_ref, (0, outputs));
+        }
+
+        Ok(schedule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use data::{Fabricate, Item};
+
+    use crate::test_support::{self, db};
+
+    use super::*;
+
+    fn fabricate(recipe: &[(&str, i32)], fabricator: Fabricator, time: f32) -> Fabricate {
+        Fabricate {
+            suitable_fabricators: vec![fabricator],
+            time,
+            ..test_support::fabricate(recipe, 1)
+        }
+    }
+
+    fn item(id: &str, fabricate: Option<Fabricate>) -> Item {
+        Item {
+            fabricate: fabricate.into_iter().collect(),
+            ..test_support::item(id)
+        }
+    }
+
+    #[test]
+    fn skill_changes_time() {
+        let mut recipe = fabricate(&[], Fabricator::Fabricator, 10.0);
+        recipe.required_skills.insert(Skill::Mechanical, 40);
+        let skills = |level| [(Skill::Mechanical, level)].into_iter().collect();
+        assert_eq!(fabrication_time(&recipe, &skills(40)), 10.0);
+        assert_eq!(fabrication_time(&recipe, &skills(140)), 5.0);
+        assert!((fabrication_time(&recipe, &skills(20)) - 10.0 / 0.16).abs() < 0.01);
+    }
+
+    #[test]
+    fn no_requirements_is_full_success() {
+        let recipe = fabricate(&[], Fabricator::Fabricator, 10.0);
+        assert_eq!(fabrication_time(&recipe, &BTreeMap::new()), 5.0);
+    }
+
+    #[test]
+    fn runs_in_parallel_after_ingredients() {
+        let db = db(vec![
+            item("copper", None),
+            item(
+                "wire",
+                Some(fabricate(&[("copper", 1)], Fabricator::Fabricator, 10.0)),
+            ),
+            item(
+                "fuse",
+                Some(fabricate(&[("wire", 2)], Fabricator::Fabricator, 5.0)),
+            ),
+            item(
+                "bandage",
+                Some(fabricate(&[], Fabricator::MedicalFabricator, 1.0)),
+            ),
+        ]);
+        let [fuse, bandage] = ["fuse", "bandage"].map(|id| db.new_item_ref(id).unwrap());
+        let bom = db.bill_of_materials(&[(fuse, 1), (bandage, 1)]).unwrap();
+
+        let two = [(Fabricator::Fabricator, 2)].into_iter().collect();
+        let schedule = db.schedule(&bom, &two, &BTreeMap::new()).unwrap();
+        // Both wires at once, then the fuse, each at half the listed time.
+        assert_eq!(schedule.duration(), 7.5);
+        // No medical fabricator aboard for the bandage.
+        assert_eq!(schedule.unscheduled.len(), 1);
+
+        let one = [(Fabricator::Fabricator, 1)].into_iter().collect();
+        let schedule = db.schedule(&bom, &one, &BTreeMap::new()).unwrap();
+        assert_eq!(schedule.duration(), 12.5);
+    }
+}
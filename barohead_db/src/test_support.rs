@@ -0,0 +1,86 @@
+//! Small item databases for tests, here and in the crates built on this one.
+//!
+//! Ids starting with `#` name a tag rather than an item, as recipes can.
+
+use std::collections::BTreeMap;
+
+use barohead_data::items::{
+    Deconstruct, Fabricate, Fabricator, Item, ItemDB, ItemRef, Language, ProducedItem, RequiredItem,
+};
+
+use crate::DB;
+
+pub fn item(id: &str) -> Item {
+    Item {
+        id: id.to_owned(),
+        ..Default::default()
+    }
+}
+
+pub fn required(id: &str, amount: i32) -> RequiredItem {
+    RequiredItem {
+        item: match id.strip_prefix('#') {
+            Some(tag) => ItemRef::Tag(tag.to_owned()),
+            None => ItemRef::Id(id.to_owned()),
+        },
+        amount,
+        condition: None,
+    }
+}
+
+/// A fabricator recipe taking a second, with no skills or recipe needed.
+pub fn fabricate(recipe: &[(&str, i32)], amount: i32) -> Fabricate {
+    Fabricate {
+        suitable_fabricators: vec![Fabricator::Fabricator],
+        time: 1.0,
+        required_items: recipe
+            .iter()
+            .map(|(id, amount)| required(id, *amount))
+            .collect(),
+        required_skills: BTreeMap::new(),
+        requires_recipe: false,
+        out_condition: 1.0,
+        amount,
+        recycle: false,
+    }
+}
+
+pub fn produced(id: &str, amount: i32) -> ProducedItem {
+    ProducedItem {
+        id: id.to_owned(),
+        amount,
+        mincondition: None,
+    }
+}
+
+/// Deconstruction taking a second, with nothing needed.
+pub fn deconstruct(outputs: &[(&str, i32)]) -> Deconstruct {
+    Deconstruct {
+        time: 1.0,
+        required_items: vec![],
+        required_skills: BTreeMap::new(),
+        items: outputs
+            .iter()
+            .map(|(id, amount)| produced(id, *amount))
+            .collect(),
+    }
+}
+
+/// English texts from key and value pairs.
+pub fn itemdb(items: Vec<Item>, texts: &[(&str, &str)]) -> ItemDB {
+    let texts = texts
+        .iter()
+        .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+        .collect();
+    ItemDB {
+        texts: [(Language::English, texts)].into_iter().collect(),
+        items,
+        packages: vec![],
+        icon_atlas: None,
+    }
+}
+
+/// Panics if the items don't index, which is a mistake in the test.
+pub fn db(items: Vec<Item>) -> DB {
+    DB::new(itemdb(items, &[])).unwrap()
+}
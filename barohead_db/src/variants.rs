@@ -0,0 +1,67 @@
+use crate::{Error, ItemRef, DB};
+
+impl DB {
+    /// The item this one is a `variantof`, if it's in the database.
+    pub fn variant_base(&self, item_ref: ItemRef) -> Result<Option<ItemRef>, Error> {
+        Ok(self
+            .get_item(item_ref)?
+            .variant_of
+            .as_deref()
+            .and_then(|base| self.new_item_ref(base).ok()))
+    }
+
+    /// Items that are a `variantof` this one. Only direct variants, not
+    /// variants of those.
+    pub fn variants(&self, item_ref: ItemRef) -> Vec<ItemRef> {
+        self.item_refs()
+            .filter(|variant| self.variant_base(*variant).ok().flatten() == Some(item_ref))
+            .collect()
+    }
+
+    /// Items carrying a tag, which are the ones that can fill a recipe slot
+    /// asking for it.
+    pub fn items_with_tag(&self, tag: &str) -> Vec<ItemRef> {
+        self.item_refs()
+            .filter(|item_ref| {
+                self.get_item(*item_ref)
+                    .is_ok_and(|item| item.tags.iter().any(|item_tag| item_tag == tag))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use barohead_data::items::Item;
+
+    use crate::test_support::{self, db};
+
+    fn item(id: &str, variant_of: Option<&str>, tags: &[&str]) -> Item {
+        Item {
+            variant_of: variant_of.map(str::to_owned),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..test_support::item(id)
+        }
+    }
+
+    #[test]
+    fn finds_variants_and_tags() {
+        let db = db(vec![
+            item("depthcharge", None, &["explosive"]),
+            item("depthchargecheap", Some("depthcharge"), &["explosive"]),
+            item("depthchargecheaper", Some("depthchargecheap"), &[]),
+            item("lonely", Some("removedbymod"), &[]),
+        ]);
+        let depthcharge = db.new_item_ref("depthcharge").unwrap();
+        let cheap = db.new_item_ref("depthchargecheap").unwrap();
+        let cheaper = db.new_item_ref("depthchargecheaper").unwrap();
+        let lonely = db.new_item_ref("lonely").unwrap();
+
+        assert_eq!(db.variants(depthcharge), vec![cheap]);
+        assert_eq!(db.variants(cheap), vec![cheaper]);
+        assert_eq!(db.variant_base(cheaper).unwrap(), Some(cheap));
+        assert_eq!(db.variant_base(depthcharge).unwrap(), None);
+        assert_eq!(db.variant_base(lonely).unwrap(), None);
+        assert_eq!(db.items_with_tag("explosive"), vec![depthcharge, cheap]);
+    }
+}
@@ -0,0 +1,431 @@
+// Reading items out of the game's item XML, matching what the game does with
+// the parts we keep and skipping (with a warning) what we don't understand.
+
+use std::collections::BTreeMap;
+
+use crate::xml::{attr, boolean, is, list, number, numbers, Element};
+use barohead_data::items::{
+    Category, ConditionRange, Deconstruct, Fabricate, Fabricator, Icon, Item, ItemRef, Price,
+    PriceModifier, ProducedItem, Rect, RequiredItem, Skill, StoreIdentifier,
+};
+
+// What the game uses when a recipe doesn't say.
+const DEFAULT_DECONSTRUCT_TIME: f32 = 1.0;
+const DEFAULT_FABRICATE_TIME: f32 = 1.0;
+const DEFAULT_FABRICATE_AMOUNT: i32 = 1;
+
+fn fabricator(name: &str) -> Option<Fabricator> {
+    match name.to_ascii_lowercase().as_str() {
+        "fabricator" => Some(Fabricator::Fabricator),
+        "medicalfabricator" => Some(Fabricator::MedicalFabricator),
+        "vendingmachine" => Some(Fabricator::VendingMachine),
+        _ => None,
+    }
+}
+
+fn category(name: &str) -> Option<Category> {
+    Category::from_internal_name(&name.to_ascii_lowercase())
+}
+
+fn skill(name: &str) -> Option<Skill> {
+    Skill::ALL
+        .into_iter()
+        .find(|skill| skill.name().eq_ignore_ascii_case(name))
+}
+
+fn item_ref(node: &Element) -> Option<ItemRef> {
+    if let Some(id) = attr(node, "identifier") {
+        Some(ItemRef::Id(id.to_owned()))
+    } else {
+        attr(node, "tag").map(|tag| ItemRef::Tag(tag.to_owned()))
+    }
+}
+
+struct Recipe {
+    required_items: Vec<RequiredItem>,
+    required_skills: BTreeMap<Skill, i32>,
+    items: Vec<ProducedItem>,
+}
+
+fn recipe(
+    node: &Element,
+    items_are_requirements: bool,
+    warnings: &mut Vec<String>,
+) -> Result<Recipe, String> {
+    let mut recipe = Recipe {
+        required_items: vec![],
+        required_skills: BTreeMap::new(),
+        items: vec![],
+    };
+
+    for child in &node.children {
+        let name = child.name.to_ascii_lowercase();
+        match name.as_str() {
+            "item" if !items_are_requirements => {
+                let id = attr(child, "identifier").ok_or("produced item without an identifier")?;
+                recipe.items.push(ProducedItem {
+                    id: id.to_owned(),
+                    amount: number(child, "amount")?.unwrap_or(1),
+                    mincondition: number(child, "mincondition")?,
+                });
+            }
+            "item" | "requireditem" => {
+                // nucleardepthchargecheap has an empty one, which the game
+                // ignores rather than dropping the whole recipe.
+                let Some(item) = item_ref(child) else {
+                    warnings.push(format!(
+                        "skipping <{}> without an identifier or tag",
+                        child.name
+                    ));
+                    continue;
+                };
+                let min = number(child, "mincondition")?;
+                let max = number(child, "maxcondition")?;
+                recipe.required_items.push(RequiredItem {
+                    item,
+                    amount: number(child, "amount")?.unwrap_or(1),
+                    // Only requirements spelt out as such have conditions.
+                    condition: (name == "requireditem" && (min.is_some() || max.is_some()))
+                        .then_some(ConditionRange { min, max }),
+                });
+            }
+            "requiredskill" => {
+                let id = attr(child, "identifier").unwrap_or_default();
+                let Some(skill) = skill(id) else {
+                    warnings.push(format!("unknown skill {:?}", id));
+                    continue;
+                };
+                let level = number(child, "level")?.ok_or("skill without a level")?;
+                recipe.required_skills.insert(skill, level);
+            }
+            _ => warnings.push(format!("unexpected <{}> in a recipe", child.name)),
+        }
+    }
+    Ok(recipe)
+}
+
+// Stock limits are spelt both "minamount" and "minavailable", and the game
+// prefers the former.
+fn available(node: &Element, bound: &str) -> Result<Option<i32>, String> {
+    match number(node, &format!("{}amount", bound))? {
+        Some(amount) => Ok(Some(amount)),
+        None => number(node, &format!("{}available", bound)),
+    }
+}
+
+fn price(node: &Element, warnings: &mut Vec<String>) -> Result<Option<Price>, String> {
+    // See geneticmaterialhusk.
+    let Some(baseprice) = number(node, "baseprice")? else {
+        return Ok(None);
+    };
+    let mut price = Price {
+        baseprice,
+        sold: boolean(node, "sold")?.unwrap_or(true),
+        modifiers: BTreeMap::new(),
+        min_available: available(node, "min")?,
+        max_available: available(node, "max")?,
+        min_level_difficulty: number(node, "minleveldifficulty")?,
+        requires_unlock: boolean(node, "requiresunlock")?.unwrap_or(false),
+        can_be_special: boolean(node, "canbespecial")?.unwrap_or(true),
+    };
+    for modifier in node.children.iter().filter(|child| is(child, "price")) {
+        // idcardfakesootman has modifiers with no store.
+        let Some(store) = attr(modifier, "storeidentifier") else {
+            continue;
+        };
+        let Some(store) = StoreIdentifier::from_internal_name(&store.to_ascii_lowercase()) else {
+            warnings.push(format!("unknown store {:?}", store));
+            continue;
+        };
+        price.modifiers.insert(
+            store,
+            PriceModifier {
+                multiplier: number(modifier, "multiplier")?,
+                sold: boolean(modifier, "sold")?,
+                min_available: available(modifier, "min")?,
+                max_available: available(modifier, "max")?,
+                min_level_difficulty: number(modifier, "minleveldifficulty")?,
+                requires_unlock: boolean(modifier, "requiresunlock")?,
+                can_be_special: boolean(modifier, "canbespecial")?,
+            },
+        );
+    }
+    Ok(Some(price))
+}
+
+// Either a "x,y,width,height" sourcerect, or a cell in a sheet of equally
+// sized sprites. An empty rect means the whole texture, as it does in game.
+fn source_rect(node: &Element) -> Result<Option<Rect>, String> {
+    let rect = if let Some([x, y, width, height]) = numbers(node, "sourcerect")? {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    } else if let Some([width, height]) = numbers(node, "sheetelementsize")? {
+        let [column, row]: [u32; 2] = numbers(node, "sheetindex")?.unwrap_or_default();
+        Rect {
+            x: column * width,
+            y: row * height,
+            width,
+            height,
+        }
+    } else {
+        return Ok(None);
+    };
+    Ok((rect.width > 0 && rect.height > 0).then_some(rect))
+}
+
+// The inventory shows the icon if there is one and the sprite otherwise. The
+// extractor has already turned texture paths into files on disk.
+fn icon(node: &Element) -> Result<Option<Icon>, String> {
+    let sprite = ["inventoryicon", "sprite"].into_iter().find_map(|name| {
+        node.children
+            .iter()
+            .find(|child| is(child, name) && attr(child, "texture").is_some())
+    });
+    let Some(sprite) = sprite else {
+        return Ok(None);
+    };
+    Ok(Some(Icon {
+        texture: attr(sprite, "texture").unwrap().to_owned(),
+        source_rect: source_rect(sprite)?,
+    }))
+}
+
+/// Read one item element, after any `variantof` has been applied. Problems
+/// with parts of the item are reported in `warnings` and leave those parts
+/// out; an item we can't identify at all is `None`.
+pub(crate) fn parse_item(node: &Element, warnings: &mut Vec<String>) -> Option<Item> {
+    let Some(id) = attr(node, "identifier") else {
+        warnings.push(format!(
+            "<{}> {:?} has no identifier",
+            node.name,
+            attr(node, "name").unwrap_or_default()
+        ));
+        return None;
+    };
+
+    let mut item = Item {
+        id: id.to_owned(),
+        nameidentifier: attr(node, "nameidentifier").map(str::to_owned),
+        variant_of: attr(node, "variantof").map(str::to_owned),
+        tags: list(node, "tags").into_iter().map(str::to_owned).collect(),
+        categories: vec![],
+        icon: None,
+        // Filled in by pack-index, which builds the atlas.
+        atlas_rect: None,
+        fabricate: vec![],
+        deconstruct: vec![],
+        price: None,
+        // Filled in by the extractor, which knows the package.
+        provenance: None,
+    };
+    let mut item_warnings = vec![];
+
+    for name in list(node, "category") {
+        match category(name) {
+            Some(category) => item.categories.push(category),
+            None => item_warnings.push(format!("unknown category {:?}", name)),
+        }
+    }
+    // The game files anything without a category under Misc.
+    if item.categories.is_empty() {
+        item.categories.push(Category::Misc);
+    }
+
+    match icon(node) {
+        Ok(icon) => item.icon = icon,
+        Err(err) => item_warnings.push(format!("skipping icon: {}", err)),
+    }
+
+    for child in &node.children {
+        let result = match child.name.to_ascii_lowercase().as_str() {
+            "deconstruct" => recipe(child, false, &mut item_warnings).and_then(|recipe| {
+                item.deconstruct.push(Deconstruct {
+                    time: number(child, "time")?.unwrap_or(DEFAULT_DECONSTRUCT_TIME),
+                    required_items: recipe.required_items,
+                    required_skills: recipe.required_skills,
+                    items: recipe.items,
+                });
+                Ok(())
+            }),
+            "fabricate" => recipe(child, true, &mut item_warnings).and_then(|recipe| {
+                let mut suitable_fabricators = vec![];
+                for name in list(child, "suitablefabricators") {
+                    match fabricator(name) {
+                        Some(fabricator) => suitable_fabricators.push(fabricator),
+                        None => item_warnings.push(format!("unknown fabricator {:?}", name)),
+                    }
+                }
+                item.fabricate.push(Fabricate {
+                    suitable_fabricators,
+                    time: number(child, "requiredtime")?.unwrap_or(DEFAULT_FABRICATE_TIME),
+                    required_items: recipe.required_items,
+                    required_skills: recipe.required_skills,
+                    requires_recipe: boolean(child, "requiresrecipe")?.unwrap_or(false),
+                    out_condition: number(child, "outcondition")?.unwrap_or(1.0),
+                    amount: number(child, "amount")?.unwrap_or(DEFAULT_FABRICATE_AMOUNT),
+                    recycle: attr(child, "displayname")
+                        .is_some_and(|name| name.eq_ignore_ascii_case("recycleitem")),
+                });
+                Ok(())
+            }),
+            "price" => price(child, &mut item_warnings).map(|price| {
+                if price.is_some() {
+                    item.price = price;
+                }
+            }),
+            _ => Ok(()),
+        };
+        if let Err(err) = result {
+            item_warnings.push(format!("skipping <{}>: {}", child.name, err));
+        }
+    }
+
+    warnings.extend(
+        item_warnings
+            .into_iter()
+            .map(|warning| format!("{}: {}", id, warning)),
+    );
+    Some(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::xml;
+
+    fn parse(text: &str) -> (Option<Item>, Vec<String>) {
+        let mut warnings = vec![];
+        let item = parse_item(
+            &xml::parse(Path::new("test.xml"), text).unwrap(),
+            &mut warnings,
+        );
+        (item, warnings)
+    }
+
+    #[test]
+    fn parses_recipes_and_prices() {
+        let (item, warnings) = parse(
+            r#"<Item identifier="fuel" NameIdentifier="fuelrod" category="Material,Fuel">
+                <Price baseprice="100" sold="false" minavailable="2" requiresunlock="true">
+                    <Price storeidentifier="merchantcity" multiplier="1.5" sold="true" minamount="1" maxavailable="4" requiresunlock="false" />
+                    <Price storeidentifier="merchantmoon" />
+                </Price>
+                <Fabricate suitablefabricators="fabricator,weirdfabricator" requiredTime="30" amount="2">
+                    <RequiredSkill identifier="mechanical" level="40" />
+                    <RequiredItem tag="smallitem" mincondition="0.5" />
+                    <Item identifier="uranium" amount="2" />
+                </Fabricate>
+                <Deconstruct time="10">
+                    <Item identifier="lead" />
+                </Deconstruct>
+                <Fabricate displayname="recycleitem" />
+                <Sprite texture="fuel.png" sourcerect="0,0,10,10" />
+                <InventoryIcon texture="icons.png" sheetindex="2,1" sheetelementsize="64,32" />
+            </Item>"#,
+        );
+        let item = item.unwrap();
+        assert_eq!(item.id, "fuel");
+        assert_eq!(item.nameidentifier.as_deref(), Some("fuelrod"));
+        assert_eq!(item.categories, vec![Category::Material, Category::Fuel]);
+        assert_eq!(
+            item.icon,
+            Some(Icon {
+                texture: "icons.png".to_owned(),
+                source_rect: Some(Rect {
+                    x: 128,
+                    y: 32,
+                    width: 64,
+                    height: 32,
+                }),
+            })
+        );
+
+        let fabricate = &item.fabricate[0];
+        assert_eq!(fabricate.suitable_fabricators, vec![Fabricator::Fabricator]);
+        assert_eq!((fabricate.time, fabricate.amount), (30.0, 2));
+        assert_eq!(fabricate.required_skills[&Skill::Mechanical], 40);
+        assert_eq!(
+            fabricate.required_items,
+            vec![
+                RequiredItem {
+                    item: ItemRef::Tag("smallitem".to_owned()),
+                    amount: 1,
+                    condition: Some(ConditionRange {
+                        min: Some(0.5),
+                        max: None
+                    }),
+                },
+                RequiredItem {
+                    item: ItemRef::Id("uranium".to_owned()),
+                    amount: 2,
+                    condition: None,
+                },
+            ]
+        );
+        assert!(item.fabricate[1].recycle);
+        assert_eq!(item.deconstruct[0].items[0].id, "lead");
+
+        let price = item.price.unwrap();
+        assert!(!price.sold);
+        assert_eq!(
+            price.modifiers[&StoreIdentifier::MerchantCity].multiplier,
+            Some(1.5)
+        );
+        assert_eq!((price.min_available, price.max_available), (Some(2), None));
+        assert!(price.requires_unlock && price.can_be_special);
+        let city = &price.modifiers[&StoreIdentifier::MerchantCity];
+        assert_eq!((city.min_available, city.max_available), (Some(1), Some(4)));
+        assert_eq!(
+            (city.requires_unlock, city.can_be_special),
+            (Some(false), None)
+        );
+
+        assert_eq!(
+            warnings,
+            vec![
+                "fuel: unknown store \"merchantmoon\"",
+                "fuel: unknown fabricator \"weirdfabricator\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_broken_recipes() {
+        let (item, warnings) = parse(
+            r#"<Item identifier="thing">
+                <Fabricate amount="lots" />
+                <Deconstruct><Item tag="metal" /></Deconstruct>
+            </Item>"#,
+        );
+        let item = item.unwrap();
+        assert!(item.fabricate.is_empty());
+        assert!(item.deconstruct.is_empty());
+        assert_eq!(warnings.len(), 2);
+
+        let (item, warnings) = parse(
+            r#"<Item identifier="charge">
+                <Fabricate>
+                    <RequiredItem />
+                    <RequiredItem identifier="uranium" />
+                </Fabricate>
+            </Item>"#,
+        );
+        let required_items = &item.unwrap().fabricate[0].required_items;
+        assert_eq!(required_items.len(), 1);
+        assert_eq!(required_items[0].item, ItemRef::Id("uranium".to_owned()));
+        assert_eq!(
+            warnings,
+            vec!["charge: skipping <RequiredItem> without an identifier or tag"]
+        );
+
+        let (item, warnings) = parse(r#"<Item name="nameless" />"#);
+        assert!(item.is_none());
+        assert_eq!(warnings.len(), 1);
+    }
+}
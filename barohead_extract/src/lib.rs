@@ -0,0 +1,485 @@
+//! Reads Barotrauma content packages straight from the game and mod
+//! directories into a [`barohead_data::items::ItemDB`].
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use std::path::Path;
+//! use barohead_extract::{ContentPackage, Extractor};
+//!
+//! let game_dir = Path::new("Barotrauma");
+//! let vanilla = ContentPackage::load(
+//!     &game_dir.join("Content/ContentPackages/Vanilla.xml"),
+//!     game_dir,
+//!     &[],
+//! )?;
+//! let mut extractor = Extractor::default();
+//! extractor.add_package(&vanilla)?;
+//! let extracted = extractor.finish();
+//! println!("{} items", extracted.itemdb.items.len());
+//! # Ok(())
+//! # }
+//! ```
+
+mod error;
+mod items;
+mod package;
+mod xml;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use barohead_data::items::{ItemDB, Language, Provenance};
+
+pub use error::Error;
+pub use package::{resolve_path, ContentPackage};
+
+use xml::Element;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Extracted {
+    pub itemdb: ItemDB,
+    /// Things that were skipped or looked wrong, which are usually a mod's
+    /// mistake rather than ours.
+    pub warnings: Vec<String>,
+}
+
+// An item as written, before any variantof is applied.
+#[derive(Debug)]
+struct Definition {
+    element: Element,
+    path: PathBuf,
+    provenance: Provenance,
+}
+
+/// Applies content packages one after another, in load order, so that later
+/// packages override earlier ones.
+#[derive(Debug, Default)]
+pub struct Extractor {
+    items: Vec<Definition>,
+    positions: BTreeMap<String, usize>,
+    packages: Vec<ContentPackage>,
+    texts: BTreeMap<Language, BTreeMap<String, String>>,
+    warnings: Vec<String>,
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|err| Error::Io(path.to_owned(), err))
+}
+
+impl Extractor {
+    pub fn add_package(&mut self, package: &ContentPackage) -> Result<(), Error> {
+        self.packages.push(package.clone());
+        for path in &package.item_files {
+            let root = xml::parse(path, &read(path)?)?;
+            self.add_items(package, path, root, false);
+        }
+        for path in &package.text_files {
+            let root = xml::parse(path, &read(path)?)?;
+            self.add_texts(&root);
+        }
+        Ok(())
+    }
+
+    // Items can be wrapped in <Items> and <Override> in any combination, or
+    // stand alone as the whole file.
+    fn add_items(
+        &mut self,
+        package: &ContentPackage,
+        path: &Path,
+        element: Element,
+        is_override: bool,
+    ) {
+        if xml::is(&element, "items") || xml::is(&element, "override") {
+            let is_override = is_override || xml::is(&element, "override");
+            for child in element.children {
+                self.add_items(package, path, child, is_override);
+            }
+            return;
+        }
+
+        let mut element = element;
+        self.resolve_textures(package, path, &mut element);
+
+        let Some(id) = xml::attr(&element, "identifier").map(str::to_owned) else {
+            self.warnings.push(format!(
+                "{}: <{}> {:?} has no identifier",
+                path.display(),
+                element.name,
+                xml::attr(&element, "name").unwrap_or_default()
+            ));
+            return;
+        };
+        let mut definition = Definition {
+            element,
+            path: path.to_owned(),
+            provenance: Provenance {
+                package: package.name.clone(),
+                overrides: vec![],
+                inherits: vec![],
+            },
+        };
+
+        match self.positions.get(&id) {
+            Some(idx) => {
+                if !is_override {
+                    self.warnings.push(format!(
+                        "{}: {} redefined without <Override>",
+                        path.display(),
+                        id
+                    ));
+                }
+                let previous = &mut self.items[*idx].provenance;
+                definition.provenance.overrides = std::mem::take(&mut previous.overrides);
+                definition
+                    .provenance
+                    .overrides
+                    .push(std::mem::take(&mut previous.package));
+                self.items[*idx] = definition;
+            }
+            None => {
+                self.positions.insert(id, self.items.len());
+                self.items.push(definition);
+            }
+        }
+    }
+
+    // Where a texture is depends on the file that names it, so this is done
+    // before a variant can copy the path into an item from somewhere else.
+    fn resolve_textures(&mut self, package: &ContentPackage, path: &Path, element: &mut Element) {
+        for child in &mut element.children {
+            if !xml::is(child, "inventoryicon") && !xml::is(child, "sprite") {
+                continue;
+            }
+            for (name, value) in &mut child.attributes {
+                if !name.eq_ignore_ascii_case("texture") {
+                    continue;
+                }
+                match package.resolve_texture(value, path, &self.packages) {
+                    Some(texture) => *value = texture.to_string_lossy().into_owned(),
+                    None => self.warnings.push(format!(
+                        "{}: can't resolve texture {}",
+                        path.display(),
+                        value
+                    )),
+                }
+            }
+        }
+    }
+
+    // Only English is kept for now, and only the names and descriptions of
+    // items and the names of stores.
+    fn add_texts(&mut self, element: &Element) {
+        if xml::is(element, "override") {
+            for child in &element.children {
+                self.add_texts(child);
+            }
+            return;
+        }
+        if !xml::is(element, "infotexts")
+            || !xml::attr(element, "language")
+                .is_some_and(|language| language.eq_ignore_ascii_case("english"))
+        {
+            return;
+        }
+
+        let texts = self.texts.entry(Language::English).or_default();
+        for child in &element.children {
+            let key = &child.name;
+            if key.starts_with("entityname")
+                || key.starts_with("entitydescription")
+                || key.starts_with("storename")
+            {
+                texts.insert(key.clone(), child.text.clone().unwrap_or_default());
+            }
+        }
+    }
+
+    // Variants are built from the final definition of their base, whichever
+    // package that came from, and can themselves be bases.
+    fn resolve(
+        &self,
+        idx: usize,
+        resolved: &mut Vec<Option<Option<Element>>>,
+        warnings: &mut Vec<String>,
+    ) -> Option<Element> {
+        if let Some(element) = &resolved[idx] {
+            return element.clone();
+        }
+        // Marks this one as in progress, so a loop comes back as missing.
+        resolved[idx] = Some(None);
+
+        let definition = &self.items[idx];
+        let element = match xml::attr(&definition.element, "variantof") {
+            None => Some(definition.element.clone()),
+            Some(base) => match self.positions.get(base) {
+                None => {
+                    warnings.push(format!(
+                        "{}: {} is a variant of unknown item {}",
+                        definition.path.display(),
+                        xml::attr(&definition.element, "identifier").unwrap_or_default(),
+                        base
+                    ));
+                    None
+                }
+                Some(base_idx) => match self.resolve(*base_idx, resolved, warnings) {
+                    Some(base) => {
+                        let mut variant_warnings = vec![];
+                        let element = base.variant(&definition.element, &mut variant_warnings);
+                        warnings.extend(variant_warnings.into_iter().map(|warning| {
+                            format!(
+                                "{}: {}: {}",
+                                definition.path.display(),
+                                xml::attr(&definition.element, "identifier").unwrap_or_default(),
+                                warning
+                            )
+                        }));
+                        Some(element)
+                    }
+                    None => {
+                        warnings.push(format!(
+                            "{}: {} is a variant of {}, which couldn't be built",
+                            definition.path.display(),
+                            xml::attr(&definition.element, "identifier").unwrap_or_default(),
+                            base
+                        ));
+                        None
+                    }
+                },
+            },
+        };
+        resolved[idx] = Some(element.clone());
+        element
+    }
+
+    // The packages a resolved variant copied anything from. Only resolved
+    // items get here, so the chain of bases always ends.
+    fn inherited_packages(&self, idx: usize) -> Vec<String> {
+        let own = &self.items[idx].provenance.package;
+        let mut packages: Vec<String> = vec![];
+        let mut definition = &self.items[idx];
+        while let Some(base_idx) =
+            xml::attr(&definition.element, "variantof").and_then(|base| self.positions.get(base))
+        {
+            definition = &self.items[*base_idx];
+            let package = &definition.provenance.package;
+            if package != own && !packages.contains(package) {
+                packages.push(package.clone());
+            }
+        }
+        packages
+    }
+
+    pub fn finish(self) -> Extracted {
+        let mut warnings = self.warnings.clone();
+        let mut resolved = vec![None; self.items.len()];
+        let mut items = vec![];
+        for idx in 0..self.items.len() {
+            let Some(element) = self.resolve(idx, &mut resolved, &mut warnings) else {
+                continue;
+            };
+            let definition = &self.items[idx];
+            let mut item_warnings = vec![];
+            let item = items::parse_item(&element, &mut item_warnings);
+            warnings.extend(
+                item_warnings
+                    .into_iter()
+                    .map(|warning| format!("{}: {}", definition.path.display(), warning)),
+            );
+            if let Some(mut item) = item {
+                item.provenance = Some(Provenance {
+                    inherits: self.inherited_packages(idx),
+                    ..definition.provenance.clone()
+                });
+                items.push(item);
+            }
+        }
+
+        Extracted {
+            itemdb: ItemDB {
+                texts: self.texts,
+                items,
+                packages: self
+                    .packages
+                    .into_iter()
+                    .map(|package| package.name)
+                    .collect(),
+                // Packed later, by pack-index.
+                icon_atlas: None,
+            },
+            warnings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    // A throwaway directory of files for one test.
+    struct Fixture(PathBuf);
+
+    impl Fixture {
+        fn new(name: &str, files: &[(&str, &str)]) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "barohead-extract-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            for (path, contents) in files {
+                let path = dir.join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, contents).unwrap();
+            }
+            Fixture(dir)
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn applies_packages_in_order() {
+        let fixture = Fixture::new(
+            "order",
+            &[
+                (
+                    "Content/ContentPackages/Vanilla.xml",
+                    r#"<contentpackage name="Vanilla" gameversion="1.0">
+                        <Item file="Content/Items/items.xml" />
+                        <Text file="Content/Texts/english.xml" />
+                    </contentpackage>"#,
+                ),
+                (
+                    "Content/Items/items.xml",
+                    r#"<Items>
+                        <Item identifier="wire"><Price baseprice="10" /></Item>
+                        <Item identifier="fuse">
+                            <Sprite texture="Content/Items/fuse.png" sourcerect="0,0,16,16" />
+                        </Item>
+                    </Items>"#,
+                ),
+                (
+                    "Content/Texts/english.xml",
+                    r#"<infotexts language="English">
+                        <entityname.wire>Wire</entityname.wire>
+                        <entitydescription.wire>Long</entitydescription.wire>
+                        <dialog.wire>Unrelated</dialog.wire>
+                    </infotexts>"#,
+                ),
+                (
+                    "LocalMods/Better Wire/filelist.xml",
+                    r#"<contentpackage name="Better Wire">
+                        <Item file="%ModDir%/items.xml" />
+                        <Text file="%ModDir%/Texts.xml" />
+                    </contentpackage>"#,
+                ),
+                (
+                    "LocalMods/Better Wire/Items.xml",
+                    r#"<Items>
+                        <Override>
+                            <Item identifier="wire">
+                                <Price baseprice="5" />
+                                <InventoryIcon texture="%ModDir%/wire.png" />
+                            </Item>
+                        </Override>
+                        <Item identifier="shinywire" variantof="wire" tags="shiny">
+                            <InventoryIcon texture="shiny.png" />
+                        </Item>
+                        <Item identifier="bigfuse" variantof="fuse" />
+                    </Items>"#,
+                ),
+                (
+                    "LocalMods/Better Wire/texts.xml",
+                    r#"<infotexts language="English">
+                        <entityname.wire>Better Wire</entityname.wire>
+                    </infotexts>"#,
+                ),
+            ],
+        );
+        let game_dir = &fixture.0;
+
+        let vanilla = ContentPackage::load(
+            &game_dir.join("Content/ContentPackages/Vanilla.xml"),
+            game_dir,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(vanilla.game_version.as_deref(), Some("1.0"));
+        // The files are found despite the mod getting their case wrong.
+        let better_wire = ContentPackage::load(
+            &game_dir.join("LocalMods/Better Wire/filelist.xml"),
+            game_dir,
+            std::slice::from_ref(&vanilla),
+        )
+        .unwrap();
+
+        let mut extractor = Extractor::default();
+        extractor.add_package(&vanilla).unwrap();
+        extractor.add_package(&better_wire).unwrap();
+        let extracted = extractor.finish();
+
+        let ids = extracted
+            .itemdb
+            .items
+            .iter()
+            .map(|item| item.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["wire", "fuse", "shinywire", "bigfuse"]);
+        assert_eq!(
+            extracted.itemdb.items[0].price.as_ref().unwrap().baseprice,
+            5
+        );
+        assert_eq!(
+            extracted.itemdb.items[0].provenance,
+            Some(Provenance {
+                package: "Better Wire".to_owned(),
+                overrides: vec!["Vanilla".to_owned()],
+                inherits: vec![],
+            })
+        );
+        assert_eq!(
+            extracted.itemdb.items[1]
+                .provenance
+                .as_ref()
+                .unwrap()
+                .package,
+            "Vanilla"
+        );
+        assert_eq!(extracted.itemdb.packages, vec!["Vanilla", "Better Wire"]);
+        // Variants build on the base as overridden, not as first defined.
+        let shinywire = &extracted.itemdb.items[2];
+        assert_eq!(shinywire.variant_of.as_deref(), Some("wire"));
+        assert_eq!(shinywire.tags, vec!["shiny"]);
+        assert_eq!(shinywire.price.as_ref().unwrap().baseprice, 5);
+        assert!(shinywire.provenance.as_ref().unwrap().inherits.is_empty());
+        // A mod's variant of a base game item still has the base game's
+        // recipes and price.
+        let bigfuse = extracted.itemdb.items[3].provenance.as_ref().unwrap();
+        assert_eq!(bigfuse.package, "Better Wire");
+        assert_eq!(bigfuse.inherits, vec!["Vanilla"]);
+        // Textures are found relative to the package, or to the item file for
+        // a bare file name.
+        let texture =
+            |idx: usize| PathBuf::from(&extracted.itemdb.items[idx].icon.as_ref().unwrap().texture);
+        assert_eq!(texture(0), game_dir.join("LocalMods/Better Wire/wire.png"));
+        assert_eq!(texture(1), game_dir.join("Content/Items/fuse.png"));
+        assert_eq!(texture(2), game_dir.join("LocalMods/Better Wire/shiny.png"));
+        assert_eq!(
+            extracted.itemdb.texts[&Language::English],
+            [
+                ("entitydescription.wire".to_owned(), "Long".to_owned()),
+                ("entityname.wire".to_owned(), "Better Wire".to_owned()),
+            ]
+            .into_iter()
+            .collect()
+        );
+        assert!(extracted.warnings.is_empty());
+    }
+}
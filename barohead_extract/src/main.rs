@@ -0,0 +1,99 @@
+// Extract the item database from a game install, plus any mods, as JSON for
+// pack-index.
+//
+//   extract-content --game-dir ~/Barotrauma \
+//       --package ~/Barotrauma/LocalMods/Foo/filelist.xml > items.json
+//
+// Vanilla always comes first, then each --package in the order given, which
+// should match the server's load order.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::exit;
+
+use barohead_extract::{ContentPackage, Extractor};
+
+struct Args {
+    game_dir: PathBuf,
+    packages: Vec<PathBuf>,
+    quiet: bool,
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: extract-content --game-dir DIR [--package FILELIST]... [--quiet] > items.json"
+    );
+    exit(1);
+}
+
+fn parse_args() -> Args {
+    let mut game_dir = None;
+    let mut packages = vec![];
+    let mut quiet = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--game-dir" => game_dir = Some(PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            "--package" => packages.push(PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            "--quiet" => quiet = true,
+            _ => usage(),
+        }
+    }
+    Args {
+        game_dir: game_dir.unwrap_or_else(|| usage()),
+        packages,
+        quiet,
+    }
+}
+
+fn main() {
+    let args = parse_args();
+
+    let mut filelists = vec![args.game_dir.join("Content/ContentPackages/Vanilla.xml")];
+    filelists.extend(args.packages);
+
+    let mut packages: Vec<ContentPackage> = vec![];
+    let mut extractor = Extractor::default();
+    for filelist in &filelists {
+        let package = ContentPackage::load(filelist, &args.game_dir, &packages)
+            .and_then(|package| extractor.add_package(&package).map(|_| package))
+            .unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                exit(1);
+            });
+        packages.push(package);
+    }
+    let extracted = extractor.finish();
+
+    if !args.quiet {
+        for warning in &extracted.warnings {
+            eprintln!("warning: {}", warning);
+        }
+    }
+
+    let mut defined: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for provenance in extracted
+        .itemdb
+        .items
+        .iter()
+        .flat_map(|item| &item.provenance)
+    {
+        let counts = defined.entry(provenance.package.as_str()).or_default();
+        counts.0 += 1;
+        if !provenance.overrides.is_empty() {
+            counts.1 += 1;
+        }
+    }
+    for package in &packages {
+        let (items, overrides) = defined
+            .get(package.name.as_str())
+            .copied()
+            .unwrap_or_default();
+        eprintln!(
+            "{}: {} items, {} overriding earlier packages",
+            package.name, items, overrides
+        );
+    }
+
+    println!("{}", serde_json::to_string(&extracted.itemdb).unwrap());
+}
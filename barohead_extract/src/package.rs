@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::xml::{self, attr};
+use crate::Error;
+
+/// A content package's `filelist.xml`, with the files it lists resolved to
+/// paths on disk.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ContentPackage {
+    pub name: String,
+    pub game_version: Option<String>,
+    pub steam_workshop_id: Option<String>,
+    /// Where `%ModDir%` points.
+    pub dir: PathBuf,
+    /// Where paths without a `%ModDir%` point.
+    pub game_dir: PathBuf,
+    pub item_files: Vec<PathBuf>,
+    pub text_files: Vec<PathBuf>,
+}
+
+/// Find a path the way Windows would, since packages are written there and
+/// don't always get the case of their own files right.
+pub fn resolve_path(path: &Path) -> PathBuf {
+    if path.exists() {
+        return path.to_owned();
+    }
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        let Component::Normal(name) = component else {
+            resolved.push(component);
+            continue;
+        };
+        let exact = resolved.join(name);
+        if exact.exists() {
+            resolved = exact;
+            continue;
+        }
+        let dir = if resolved.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            &resolved
+        };
+        let found = fs::read_dir(dir).ok().and_then(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name())
+                .find(|entry| entry.eq_ignore_ascii_case(name))
+        });
+        resolved.push(found.as_deref().unwrap_or(name));
+    }
+    resolved
+}
+
+impl ContentPackage {
+    /// Read a package's file list. Paths that don't start with `%ModDir%` are
+    /// relative to the game directory, and `%ModDir:<name or id>%` points at
+    /// one of the `others` loaded before this one.
+    pub fn load(
+        filelist: &Path,
+        game_dir: &Path,
+        others: &[ContentPackage],
+    ) -> Result<ContentPackage, Error> {
+        let filelist = resolve_path(filelist);
+        let text = fs::read_to_string(&filelist).map_err(|err| Error::Io(filelist.clone(), err))?;
+        let root = xml::parse(&filelist, &text)?;
+        if !xml::is(&root, "contentpackage") {
+            return Err(Error::Invalid(
+                filelist,
+                format!("expected <contentpackage>, found <{}>", root.name),
+            ));
+        }
+
+        let dir = filelist.parent().unwrap_or(Path::new(".")).to_owned();
+        let mut package = ContentPackage {
+            name: attr(&root, "name").unwrap_or("Unnamed").to_owned(),
+            game_version: attr(&root, "gameversion").map(str::to_owned),
+            steam_workshop_id: attr(&root, "steamworkshopid").map(str::to_owned),
+            dir,
+            game_dir: game_dir.to_owned(),
+            item_files: vec![],
+            text_files: vec![],
+        };
+
+        for child in &root.children {
+            let Some(file) = attr(child, "file") else {
+                continue;
+            };
+            let path = package.resolve(file, others).ok_or_else(|| {
+                Error::Invalid(filelist.clone(), format!("can't resolve {}", file))
+            })?;
+            match child.name.to_ascii_lowercase().as_str() {
+                "item" => package.item_files.push(path),
+                "text" => package.text_files.push(path),
+                _ => {}
+            }
+        }
+        Ok(package)
+    }
+
+    /// Find a texture named in an item file. A bare file name is next to the
+    /// item file, and anything else is resolved like a file in the file list.
+    pub fn resolve_texture(
+        &self,
+        texture: &str,
+        item_file: &Path,
+        others: &[ContentPackage],
+    ) -> Option<PathBuf> {
+        if texture.contains(['/', '\\']) {
+            return self.resolve(texture, others);
+        }
+        let dir = item_file.parent().unwrap_or(Path::new("."));
+        Some(resolve_path(&dir.join(texture)))
+    }
+
+    fn resolve(&self, file: &str, others: &[ContentPackage]) -> Option<PathBuf> {
+        let file = file.replace('\\', "/");
+        let path = match file.strip_prefix('%') {
+            Some(rest) => {
+                let (var, rest) = rest.split_once('%')?;
+                let dir = match var.split_once(':') {
+                    None if var.eq_ignore_ascii_case("moddir") => &self.dir,
+                    Some((var, name)) if var.eq_ignore_ascii_case("moddir") => {
+                        &others
+                            .iter()
+                            .find(|other| {
+                                other.name == name
+                                    || other.steam_workshop_id.as_deref() == Some(name)
+                            })?
+                            .dir
+                    }
+                    _ => return None,
+                };
+                dir.join(rest.trim_start_matches('/'))
+            }
+            None => self.game_dir.join(file),
+        };
+        Some(resolve_path(&path))
+    }
+}
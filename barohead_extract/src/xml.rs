@@ -0,0 +1,264 @@
+// An owned XML tree with small helpers. The game matches element and
+// attribute names without regard to case, and mods lean on that, so we do too.
+//
+// Items are kept as XML until every package is loaded, because a variant is
+// built from its base's XML, and the base can come from anywhere.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use roxmltree::{Document, Node, ParsingOptions};
+
+use crate::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    pub text: Option<String>,
+}
+
+impl Element {
+    fn from_node(node: Node) -> Self {
+        Element {
+            name: node.tag_name().name().to_owned(),
+            attributes: node
+                .attributes()
+                .map(|attribute| (attribute.name().to_owned(), attribute.value().to_owned()))
+                .collect(),
+            children: node
+                .children()
+                .filter(|child| child.is_element())
+                .map(Element::from_node)
+                .collect(),
+            text: node.text().map(str::to_owned),
+        }
+    }
+
+    /// This element with `variant` applied on top, the way the game builds
+    /// `variantof` items. Attributes that can't be applied keep the base's
+    /// value and are reported in `warnings`.
+    pub fn variant(&self, variant: &Element, warnings: &mut Vec<String>) -> Element {
+        let mut merged = self.clone();
+        merged.name = variant.name.clone();
+        merged.replace_with(variant, warnings);
+        merged
+    }
+
+    // Attributes are replaced, or scaled with "*2" or offset with "+1".
+    // Children are matched by name and position among those with the same
+    // name; an empty one removes its match and an unmatched one is added.
+    fn replace_with(&mut self, replacement: &Element, warnings: &mut Vec<String>) {
+        for (name, value) in &replacement.attributes {
+            match self
+                .attributes
+                .iter_mut()
+                .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            {
+                Some((_, existing)) => match replace_value(existing, value) {
+                    Some(replaced) => *existing = replaced,
+                    None => warnings.push(format!(
+                        "can't apply {}=\"{}\" to <{} {}=\"{}\">",
+                        name, value, self.name, name, existing
+                    )),
+                },
+                None => self.attributes.push((name.clone(), value.clone())),
+            }
+        }
+
+        let mut removed = vec![];
+        for (idx, child) in replacement.children.iter().enumerate() {
+            let position = replacement.children[..idx]
+                .iter()
+                .filter(|earlier| earlier.name.eq_ignore_ascii_case(&child.name))
+                .count();
+            let found = self
+                .children
+                .iter()
+                .enumerate()
+                .filter(|(_, existing)| existing.name.eq_ignore_ascii_case(&child.name))
+                .nth(position)
+                .map(|(idx, _)| idx);
+            match found {
+                Some(idx) if child.attributes.is_empty() && child.children.is_empty() => {
+                    removed.push(idx)
+                }
+                Some(idx) => self.children[idx].replace_with(child, warnings),
+                None => self.children.push(child.clone()),
+            }
+        }
+        let mut idx = 0;
+        self.children.retain(|_| {
+            idx += 1;
+            !removed.contains(&(idx - 1))
+        });
+    }
+}
+
+// `None` if either side of a "*" or "+" isn't a number.
+fn replace_value(existing: &str, replacement: &str) -> Option<String> {
+    let (operand, apply): (&str, fn(f32, f32) -> f32) =
+        if let Some(operand) = replacement.strip_prefix('*') {
+            (operand, |a, b| a * b)
+        } else if let Some(operand) = replacement.strip_prefix('+') {
+            (operand, |a, b| a + b)
+        } else {
+            return Some(replacement.to_owned());
+        };
+    let base: f32 = existing.trim().parse().ok()?;
+    let operand: f32 = operand.trim().parse().ok()?;
+    let value = apply(base, operand);
+    // The game keeps whole numbers whole unless either side had a fraction.
+    Some(if existing.contains('.') || replacement.contains('.') {
+        value.to_string()
+    } else {
+        (value as i32).to_string()
+    })
+}
+
+pub fn parse(path: &Path, text: &str) -> Result<Element, Error> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    Document::parse_with_options(text.trim_start_matches('\u{feff}'), options)
+        .map(|doc| Element::from_node(doc.root_element()))
+        .map_err(|err| Error::Xml(path.to_owned(), err))
+}
+
+pub fn is(element: &Element, name: &str) -> bool {
+    element.name.eq_ignore_ascii_case(name)
+}
+
+pub fn attr<'a>(element: &'a Element, name: &str) -> Option<&'a str> {
+    element
+        .attributes
+        .iter()
+        .find(|(attribute, _)| attribute.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// An attribute parsed as a number, or `None` if it's missing.
+pub fn number<T: FromStr>(element: &Element, name: &str) -> Result<Option<T>, String> {
+    attr(element, name)
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid number for {}: {:?}", name, value))
+        })
+        .transpose()
+}
+
+/// A comma separated attribute with exactly `N` numbers, like a sourcerect.
+pub fn numbers<T: FromStr, const N: usize>(
+    element: &Element,
+    name: &str,
+) -> Result<Option<[T; N]>, String> {
+    attr(element, name)
+        .map(|value| {
+            value
+                .split(',')
+                .map(|part| part.trim().parse().ok())
+                .collect::<Option<Vec<T>>>()
+                .and_then(|parts| parts.try_into().ok())
+                .ok_or_else(|| format!("invalid numbers for {}: {:?}", name, value))
+        })
+        .transpose()
+}
+
+pub fn boolean(element: &Element, name: &str) -> Result<Option<bool>, String> {
+    attr(element, name)
+        .map(|value| match value.trim().to_ascii_lowercase().as_str() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(format!("invalid boolean for {}: {:?}", name, value)),
+        })
+        .transpose()
+}
+
+pub fn list<'a>(element: &'a Element, name: &str) -> Vec<&'a str> {
+    attr(element, name)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(text: &str) -> Element {
+        parse(Path::new("test.xml"), text).unwrap()
+    }
+
+    #[test]
+    fn builds_variants_like_the_game() {
+        let base = element(
+            r#"<Item identifier="depthcharge" tags="explosive">
+                <Price baseprice="100" />
+                <Fabricate requiredtime="30">
+                    <RequiredItem identifier="steel" />
+                    <RequiredItem identifier="uranium" amount="2" />
+                    <RequiredItem identifier="c4" />
+                </Fabricate>
+            </Item>"#,
+        );
+        let variant = element(
+            r#"<Item identifier="depthchargecheap" variantof="depthcharge">
+                <Price baseprice="*0.5" />
+                <Fabricate requiredtime="+10">
+                    <RequiredItem />
+                    <RequiredItem identifier="lead" amount="*2" />
+                </Fabricate>
+                <Deconstruct />
+            </Item>"#,
+        );
+
+        let expected = element(
+            r#"<Item identifier="depthchargecheap" tags="explosive" variantof="depthcharge">
+                <Price baseprice="50" />
+                <Fabricate requiredtime="40">
+                    <RequiredItem identifier="lead" amount="4" />
+                    <RequiredItem identifier="c4" />
+                </Fabricate>
+                <Deconstruct />
+            </Item>"#,
+        );
+        let strip_text = |mut element: Element| {
+            fn strip(element: &mut Element) {
+                element.text = None;
+                element.children.iter_mut().for_each(strip);
+            }
+            strip(&mut element);
+            element
+        };
+        let mut warnings = vec![];
+        assert_eq!(
+            strip_text(base.variant(&variant, &mut warnings)),
+            strip_text(expected)
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn warns_about_operands_that_are_not_numbers() {
+        let base = element(r#"<Item identifier="gun"><Price baseprice="100" /></Item>"#);
+        let variant =
+            element(r#"<Item identifier="gunx" variantof="gun"><Price baseprice="*x" /></Item>"#);
+
+        let mut warnings = vec![];
+        let merged = base.variant(&variant, &mut warnings);
+        assert_eq!(attr(&merged.children[0], "baseprice"), Some("100"));
+        assert_eq!(
+            warnings,
+            vec!["can't apply baseprice=\"*x\" to <Price baseprice=\"100\">".to_owned()]
+        );
+    }
+}
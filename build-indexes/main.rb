@@ -248,6 +248,7 @@ class ItemDB
         texts_node.children.each do |child_node|
           next unless child_node.element?
           next unless (child_node.name.start_with?("entityname") ||
+            child_node.name.start_with?("entitydescription") ||
             child_node.name.start_with?("storename"))
 
           texts[child_node.name] = child_node.children.to_s
@@ -0,0 +1,214 @@
+// Packs item icons into a single image for the web app.
+//
+// Icons are cut out of the game's textures, scaled down to fit a cell, and
+// laid out in a grid. Items showing the same icon, which most variants do,
+// share a cell.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use barohead_data::items::{Icon, IconAtlas, ItemDB, Rect};
+
+/// Twice the size thumbnails show icons at, so they stay sharp on high
+/// density screens.
+const CELL_SIZE: u32 = 64;
+
+struct Image {
+    width: u32,
+    height: u32,
+    /// RGBA, one byte per channel.
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    fn new(width: u32, height: u32) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    fn read(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let mut decoder = png::Decoder::new(BufReader::new(file));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder
+            .read_info()
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut buf)
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+        let bytes = &buf[..info.buffer_size()];
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => bytes.to_vec(),
+            png::ColorType::Rgb => bytes
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => bytes
+                .chunks_exact(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            png::ColorType::Grayscale => bytes.iter().flat_map(|g| [*g, *g, *g, 255]).collect(),
+            png::ColorType::Indexed => {
+                return Err(format!("{}: palette wasn't expanded", path.display()))
+            }
+        };
+        Ok(Image {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+
+    fn write(&self, path: &Path) -> Result<(), String> {
+        let file = File::create(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_compression(png::Compression::Best);
+        encoder.set_adaptive_filter(png::AdaptiveFilterType::Adaptive);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    fn pixel(&self, x: u32, y: u32) -> &[u8] {
+        let idx = ((y * self.width + x) * 4) as usize;
+        &self.pixels[idx..idx + 4]
+    }
+
+    // Shrinks `rect` to fit in a `size` square, never enlarging it. Each
+    // output pixel averages the block of pixels it covers, weighted by alpha
+    // so transparent pixels don't darken the edges.
+    fn fit(&self, rect: Rect, size: u32) -> Image {
+        let scale = (size as f32 / rect.width.max(rect.height) as f32).min(1.0);
+        let width = ((rect.width as f32 * scale).round() as u32).max(1);
+        let height = ((rect.height as f32 * scale).round() as u32).max(1);
+
+        let mut image = Image::new(width, height);
+        for y in 0..height {
+            let (y0, y1) = (y * rect.height / height, (y + 1) * rect.height / height);
+            for x in 0..width {
+                let (x0, x1) = (x * rect.width / width, (x + 1) * rect.width / width);
+                let mut sum = [0u32; 4];
+                for sy in y0..y1 {
+                    for sx in x0..x1 {
+                        let pixel = self.pixel(rect.x + sx, rect.y + sy);
+                        let alpha = pixel[3] as u32;
+                        for channel in 0..3 {
+                            sum[channel] += pixel[channel] as u32 * alpha;
+                        }
+                        sum[3] += alpha;
+                    }
+                }
+                let count = (x1 - x0) * (y1 - y0);
+                let idx = ((y * width + x) * 4) as usize;
+                for channel in 0..3 {
+                    image.pixels[idx + channel] =
+                        sum[channel].checked_div(sum[3]).unwrap_or_default() as u8;
+                }
+                image.pixels[idx + 3] = (sum[3] / count) as u8;
+            }
+        }
+        image
+    }
+
+    fn draw(&mut self, image: &Image, x: u32, y: u32) {
+        let row_len = (image.width * 4) as usize;
+        for row in 0..image.height {
+            let from = (row * image.width * 4) as usize;
+            let to = (((y + row) * self.width + x) * 4) as usize;
+            self.pixels[to..to + row_len].copy_from_slice(&image.pixels[from..from + row_len]);
+        }
+    }
+}
+
+// The icon's part of its texture, or a warning if there isn't one.
+fn cut_out(textures: &mut HashMap<String, Option<Image>>, icon: &Icon) -> Result<Image, String> {
+    let texture = textures
+        .entry(icon.texture.clone())
+        .or_insert_with(|| Image::read(Path::new(&icon.texture)).ok());
+    let Some(texture) = texture else {
+        return Err(format!("can't read {}", icon.texture));
+    };
+    let rect = icon.source_rect.unwrap_or(Rect {
+        x: 0,
+        y: 0,
+        width: texture.width,
+        height: texture.height,
+    });
+    if rect.x + rect.width > texture.width || rect.y + rect.height > texture.height {
+        return Err(format!(
+            "{:?} is outside {}, which is {}x{}",
+            rect, icon.texture, texture.width, texture.height
+        ));
+    }
+    Ok(texture.fit(rect, CELL_SIZE))
+}
+
+/// Draw every item's icon into one image at `path`, and note where each one
+/// went. Icons that can't be found are left out, with a warning.
+pub fn pack_icons(itemdb: &mut ItemDB, path: &Path) -> Result<Vec<String>, String> {
+    let mut warnings = vec![];
+    let mut textures = HashMap::new();
+    let mut cells: Vec<Image> = vec![];
+    let mut positions: HashMap<(String, Option<Rect>), Option<usize>> = HashMap::new();
+    let mut item_cells = vec![];
+
+    for item in &itemdb.items {
+        let Some(icon) = &item.icon else {
+            item_cells.push(None);
+            continue;
+        };
+        let key = (icon.texture.clone(), icon.source_rect);
+        let cell = *positions
+            .entry(key)
+            .or_insert_with(|| match cut_out(&mut textures, icon) {
+                Ok(image) => {
+                    cells.push(image);
+                    Some(cells.len() - 1)
+                }
+                Err(warning) => {
+                    warnings.push(format!("{}: {}", item.id, warning));
+                    None
+                }
+            });
+        item_cells.push(cell);
+    }
+
+    let columns = (cells.len() as f32).sqrt().ceil().max(1.0) as u32;
+    let rows = ((cells.len() as u32 + columns - 1) / columns).max(1);
+    let mut atlas = Image::new(columns * CELL_SIZE, rows * CELL_SIZE);
+    let rects = cells
+        .iter()
+        .enumerate()
+        .map(|(idx, image)| {
+            let idx = idx as u32;
+            let rect = Rect {
+                x: (idx % columns) * CELL_SIZE,
+                y: (idx / columns) * CELL_SIZE,
+                width: image.width,
+                height: image.height,
+            };
+            atlas.draw(image, rect.x, rect.y);
+            rect
+        })
+        .collect::<Vec<_>>();
+    atlas.write(path)?;
+
+    for (item, cell) in itemdb.items.iter_mut().zip(item_cells) {
+        item.atlas_rect = cell.map(|cell| rects[cell]);
+    }
+    itemdb.icon_atlas = Some(IconAtlas {
+        width: atlas.width,
+        height: atlas.height,
+    });
+    Ok(warnings)
+}
@@ -1,8 +1,164 @@
-use std::io;
+mod atlas;
+
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::exit;
 
 use barohead_data::items::*;
+use barohead_data::{database, packed};
+
+enum Format {
+    Bincode,
+    Packed,
+}
+
+enum Mode {
+    Pack {
+        format: Format,
+        game_version: Option<String>,
+        atlas: Option<PathBuf>,
+    },
+    Inspect(String),
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: pack-index [--format bincode|packed] [--game-version VERSION] [--atlas icons.png] < items.json > recipes.bincode");
+    eprintln!("       pack-index --inspect recipes.bincode");
+    exit(1);
+}
+
+fn parse_args() -> Mode {
+    let mut format = Format::Packed;
+    let mut game_version = None;
+    let mut atlas = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().as_deref() {
+                Some("bincode") => format = Format::Bincode,
+                Some("packed") => format = Format::Packed,
+                other => {
+                    eprintln!("Unknown format: {:?}, expected bincode or packed", other);
+                    exit(1);
+                }
+            },
+            "--game-version" => game_version = Some(args.next().unwrap_or_else(|| usage())),
+            "--atlas" => atlas = Some(PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            "--inspect" => return Mode::Inspect(args.next().unwrap_or_else(|| usage())),
+            _ => usage(),
+        }
+    }
+    Mode::Pack {
+        format,
+        game_version,
+        atlas,
+    }
+}
+
+fn inspect(path: &str) {
+    let bytes = std::fs::read(path).unwrap();
+    match database::read(&bytes) {
+        Ok((header, itemdb)) => {
+            if header.schema_version != database::SCHEMA_VERSION {
+                eprintln!(
+                    "Migrated from schema version {}, repack to upgrade",
+                    header.schema_version
+                );
+            }
+            println!("schema version: {}", header.schema_version);
+            println!(
+                "game version:   {}",
+                header.game_version.as_deref().unwrap_or("unknown")
+            );
+            println!("built at:       {}", header.build_timestamp);
+            println!("content hash:   {:016x}", header.content_hash);
+            println!("items:          {}", itemdb.items.len());
+            if !itemdb.packages.is_empty() {
+                println!("packages:       {}", itemdb.packages.join(", "));
+            }
+            if let Some(atlas) = &itemdb.icon_atlas {
+                let icons = itemdb
+                    .items
+                    .iter()
+                    .filter(|item| item.atlas_rect.is_some())
+                    .count();
+                println!(
+                    "icons:          {} in a {}x{} atlas",
+                    icons, atlas.width, atlas.height
+                );
+            }
+        }
+        Err(err) => {
+            eprintln!("{}: {}", path, err);
+            exit(1);
+        }
+    }
+}
+
+fn pack(format: Format, game_version: Option<String>, atlas: Option<PathBuf>) {
+    let mut json = String::new();
+    io::stdin().read_to_string(&mut json).unwrap();
+    let mut data: ItemDB = serde_json::from_str(&json).unwrap();
+
+    if let Some(path) = &atlas {
+        match atlas::pack_icons(&mut data, path) {
+            Ok(warnings) => {
+                for warning in warnings {
+                    eprintln!("warning: {}", warning);
+                }
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                exit(1);
+            }
+        }
+    }
+    // Texture paths only mean something on the machine that extracted them,
+    // and the database is published.
+    for item in &mut data.items {
+        item.icon = None;
+    }
+
+    let bincode = bincode::serialize(&data).unwrap();
+    let uncompressed = bincode::serialize(&packed::PackedItemDB::pack(&data)).unwrap();
+    let packed = database::write(&data, game_version).unwrap();
+
+    // Everything ends up in the browser, so make sure we notice if it gets
+    // lossy or large.
+    let (_, unpacked) = database::read(&packed).unwrap();
+    if unpacked != data {
+        eprintln!("Packed database did not round-trip");
+        exit(1);
+    }
+
+    eprintln!("Size report:");
+    for (label, size) in [
+        ("json", json.len()),
+        ("bincode", bincode.len()),
+        ("string table", uncompressed.len()),
+        ("string table+deflate", packed.len()),
+    ] {
+        eprintln!("  {:<22}{:>9} bytes", label, size);
+    }
+    if let Some(path) = &atlas {
+        let size = std::fs::metadata(path).map_or(0, |metadata| metadata.len());
+        eprintln!("  {:<22}{:>9} bytes", "icon atlas", size);
+    }
+
+    let output = match format {
+        Format::Bincode => bincode,
+        Format::Packed => packed,
+    };
+    io::stdout().write_all(&output).unwrap();
+}
 
 fn main() {
-    let data: ItemDB = serde_json::from_reader(io::stdin()).unwrap();
-    bincode::serialize_into(io::stdout(), &data).unwrap();
+    match parse_args() {
+        Mode::Pack {
+            format,
+            game_version,
+            atlas,
+        } => pack(format, game_version, atlas),
+        Mode::Inspect(path) => inspect(&path),
+    }
 }
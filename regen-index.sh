@@ -2,13 +2,22 @@
 
 set -euo pipefail
 
+# Any arguments are extra content packages (filelist.xml), in load order.
+
 emit_json() {
-  cd build-indexes
-  bundle exec main.rb
+  local args=(--game-dir . --quiet)
+  for package in "$@"; do
+    args+=(--package "$package")
+  done
+  cargo run --bin extract-content -- "${args[@]}"
+}
+
+game_version() {
+  sed -n 's/.*gameversion="\([^"]*\)".*/\1/p' Content/ContentPackages/Vanilla.xml | head -n1
 }
 
 json_to_bincode() {
-  cargo run --bin pack-index
+  cargo run --bin pack-index -- --game-version "$(game_version)" --atlas barohead/icons.png
 }
 
-emit_json | jq | json_to_bincode > barohead/recipes.bincode
+emit_json "$@" | json_to_bincode > barohead/recipes.bincode
@@ -5,4 +5,9 @@ set -euo pipefail
 cd barohead
 
 trunk build --dist dist-release --release --public-url /barohead
+cargo run --release --features ssr --bin prerender -- \
+  --db recipes.bincode \
+  --template dist-release/index.html \
+  --out dist-release \
+  --site-url https://n.gen.nz/barohead
 rsync --delete-after -r dist-release/ feyhin.cons.org.nz:/var/www/n.gen.nz/html/barohead